@@ -0,0 +1,215 @@
+use crate::*;
+
+// Azure DevOps Repos keeps the file, ref and lines in query parameters rather than the path.
+pub struct AzureDevOps<'a> {
+    url: &'a GitUrl,
+    config: &'a GrowseConfig,
+    state: &'a GrowseState,
+}
+
+pub struct AzureDevOpsProvider;
+
+impl Provider for AzureDevOpsProvider {
+    fn backends(&self) -> &[&str] {
+        &["azure"]
+    }
+
+    fn confidence(&self, url: &GitUrl, _config: &GrowseConfig) -> Confidence {
+        match url.host.as_deref() {
+            Some(host) if host.ends_with("dev.azure.com") => Confidence::Known,
+            _ => Confidence::No,
+        }
+    }
+
+    fn repo<'a>(
+        &self,
+        url: &'a GitUrl,
+        config: &'a GrowseConfig,
+        state: &'a GrowseState,
+    ) -> Box<dyn Repo + 'a> {
+        Box::new(AzureDevOps { url, config, state })
+    }
+}
+
+impl Repo for AzureDevOps<'_> {
+    fn name(&self) -> String {
+        "Azure DevOps".to_string()
+    }
+
+    fn to_repo_url_with_path_and_branch(&self) -> Result<String, GrowseError> {
+        self.to_repo_url_with_path()
+    }
+
+    fn to_repo_url_with_path(&self) -> Result<String, GrowseError> {
+        let repo_url = self.to_repo_url()?;
+        let path = self.state.path()?;
+
+        let new_url = Url::parse_with_params(
+            &repo_url,
+            &[("path", format!("/{path}")), ("version", self.version())],
+        )?;
+
+        Ok(new_url.to_string())
+    }
+
+    fn to_repo_url_with_path_and_branch_and_line_number(&self) -> Result<String, GrowseError> {
+        self.to_repo_url_with_path_and_line_number()
+    }
+
+    fn to_repo_url_with_path_and_line_number(&self) -> Result<String, GrowseError> {
+        let mut new_url = Url::parse(&self.to_repo_url_with_path()?)?;
+        self.append_lines(&mut new_url)?;
+
+        Ok(new_url.to_string())
+    }
+
+    fn to_repo_url_with_branch(&self) -> Result<String, GrowseError> {
+        let repo_url = self.to_repo_url()?;
+        let new_url = Url::parse_with_params(&repo_url, &[("version", self.version())])?;
+
+        Ok(new_url.to_string())
+    }
+
+    fn to_pull_request_url(&self) -> Result<String, GrowseError> {
+        let repo_url = self.to_repo_url()?;
+        let new_url = Url::parse_with_params(
+            &format!("{repo_url}/pullrequestcreate"),
+            &[("sourceRef", self.state.branch.as_str())],
+        )?;
+
+        Ok(new_url.to_string())
+    }
+
+    fn to_commit_url(&self) -> Result<String, GrowseError> {
+        let repo_url = self.to_repo_url()?;
+        let commit = self.state.commit()?;
+
+        Ok(format!("{repo_url}/commit/{commit}"))
+    }
+
+    fn to_compare_url(&self) -> Result<String, GrowseError> {
+        let repo_url = self.to_repo_url()?;
+        let (base, head) = self.state.compare()?;
+
+        let new_url = Url::parse_with_params(
+            &format!("{repo_url}/branchCompare"),
+            &[
+                ("baseVersion", ref_version(base)),
+                ("targetVersion", ref_version(head)),
+            ],
+        )?;
+
+        Ok(new_url.to_string())
+    }
+
+    fn to_blame_url(&self) -> Result<String, GrowseError> {
+        let mut new_url = Url::parse(&self.to_repo_url_with_path()?)?;
+        if self.state.line_number.is_some() {
+            self.append_lines(&mut new_url)?;
+        }
+        new_url.query_pairs_mut().append_pair("_a", "blame");
+
+        Ok(new_url.to_string())
+    }
+
+    fn to_history_url(&self) -> Result<String, GrowseError> {
+        let mut new_url = if self.state.path.is_some() {
+            Url::parse(&self.to_repo_url_with_path()?)?
+        } else {
+            Url::parse(&self.to_repo_url_with_branch()?)?
+        };
+        new_url.query_pairs_mut().append_pair("_a", "history");
+
+        Ok(new_url.to_string())
+    }
+
+    fn to_raw_url(&self) -> Result<String, GrowseError> {
+        let organization = self
+            .url
+            .organization
+            .clone()
+            .ok_or(GrowseError::MissingRemotePart("organization"))?;
+        let project = self
+            .url
+            .owner
+            .clone()
+            .ok_or(GrowseError::MissingRemotePart("project"))?;
+        let name = &self.url.name;
+        let path = self.state.path()?;
+        let (version, version_type) = match &self.state.commit {
+            Some(commit) => (commit.as_str(), "commit"),
+            None => (self.state.branch.as_str(), "branch"),
+        };
+
+        let base_url = self.azure_base_url()?;
+
+        let new_url = Url::parse_with_params(
+            &format!("{base_url}/{organization}/{project}/_apis/git/repositories/{name}/items"),
+            &[
+                ("path", format!("/{path}").as_str()),
+                ("versionDescriptor.version", version),
+                ("versionDescriptor.versionType", version_type),
+            ],
+        )?;
+
+        Ok(new_url.to_string())
+    }
+
+    fn to_repo_url(&self) -> Result<String, GrowseError> {
+        let base_url = self.azure_base_url()?;
+        let organization = self
+            .url
+            .organization
+            .clone()
+            .ok_or(GrowseError::MissingRemotePart("organization"))?;
+        let project = self
+            .url
+            .owner
+            .clone()
+            .ok_or(GrowseError::MissingRemotePart("project"))?;
+        let name = &self.url.name;
+
+        Ok(format!("{base_url}/{organization}/{project}/_git/{name}"))
+    }
+}
+
+impl AzureDevOps<'_> {
+    // both ssh.dev.azure.com and dev.azure.com remotes are browsed on dev.azure.com
+    fn azure_base_url(&self) -> Result<String, GrowseError> {
+        let host = self
+            .url
+            .host
+            .as_deref()
+            .ok_or(GrowseError::MissingRemotePart("host"))?;
+        Ok(self.config.web_base_url(host, "dev.azure.com"))
+    }
+
+    // GB<branch> for branches, GC<sha> for commits
+    fn version(&self) -> String {
+        match &self.state.commit {
+            Some(commit) => format!("GC{commit}"),
+            None => format!("GB{}", self.state.branch),
+        }
+    }
+
+    fn append_lines(&self, url: &mut Url) -> Result<(), GrowseError> {
+        let line_number = self.state.line_number()?;
+        let end_line_number = self.state.end_line_number.unwrap_or(line_number);
+        url.query_pairs_mut()
+            .append_pair("line", &line_number.to_string())
+            .append_pair("lineEnd", &end_line_number.to_string())
+            .append_pair("lineStartColumn", "1");
+        Ok(())
+    }
+}
+
+// Version descriptor for a fully qualified ref or commit SHA
+fn ref_version(git_ref: &str) -> String {
+    if let Some(branch) = git_ref.strip_prefix("refs/heads/") {
+        format!("GB{branch}")
+    } else if let Some(tag) = git_ref.strip_prefix("refs/tags/") {
+        format!("GT{tag}")
+    } else {
+        format!("GC{git_ref}")
+    }
+}
@@ -1,88 +1,207 @@
 use crate::*;
 
-impl Repo for BitBucket {
-    fn is_host(&self) -> bool {
-        self.url.host.as_ref().unwrap().contains("bitbucket") || self.url.port == Some(7999)
+// Bitbucket Server/Data Center, bitbucket.org is handled in bitbucket_cloud.rs
+pub struct BitBucket<'a> {
+    url: &'a GitUrl,
+    config: &'a GrowseConfig,
+    state: &'a GrowseState,
+}
+
+pub struct BitBucketProvider;
+
+impl Provider for BitBucketProvider {
+    fn backends(&self) -> &[&str] {
+        &["bitbucket-server", "bitbucket"]
     }
 
-    fn to_repo_url_with_path_and_branch(&self) -> Result<String, Box<dyn std::error::Error>> {
-        let host = self.url.host.clone().ok_or("No host found")?;
-        let owner = self.url.owner.clone().ok_or("No owner found")?;
+    fn confidence(&self, url: &GitUrl, _config: &GrowseConfig) -> Confidence {
+        match url.host.as_deref() {
+            Some("bitbucket.org") | None => Confidence::No,
+            // default ssh port of Bitbucket Server
+            Some(_) if url.port == Some(7999) => Confidence::Known,
+            Some(host) => Confidence::mentioned(host, "bitbucket"),
+        }
+    }
 
-        let branch_name = format!("refs/heads/{}", self.state.branch);
+    fn repo<'a>(
+        &self,
+        url: &'a GitUrl,
+        config: &'a GrowseConfig,
+        state: &'a GrowseState,
+    ) -> Box<dyn Repo + 'a> {
+        Box::new(BitBucket { url, config, state })
+    }
+}
+
+impl Repo for BitBucket<'_> {
+    fn name(&self) -> String {
+        "Bitbucket Server".to_string()
+    }
+
+    fn to_repo_url_with_path_and_branch(&self) -> Result<String, GrowseError> {
+        let branch_name = self.at_ref();
+        let mut new_url = self.file_url("browse")?;
+        new_url
+            .query_pairs_mut()
+            .append_pair("at", branch_name.as_str());
+
+        Ok(new_url.to_string())
+    }
+
+    fn to_repo_url_with_path(&self) -> Result<String, GrowseError> {
+        Ok(self.file_url("browse")?.to_string())
+    }
+
+    fn to_repo_url_with_path_and_branch_and_line_number(&self) -> Result<String, GrowseError> {
+        let mut new_url = Url::parse(&self.to_repo_url_with_path_and_branch()?)?;
+        let fragment = self.line_fragment()?;
+        new_url.set_fragment(Some(fragment.as_str()));
+
+        Ok(new_url.to_string())
+    }
+
+    fn to_repo_url_with_path_and_line_number(&self) -> Result<String, GrowseError> {
+        let mut new_url = self.file_url("browse")?;
+        let fragment = self.line_fragment()?;
+        new_url.set_fragment(Some(fragment.as_str()));
+
+        Ok(new_url.to_string())
+    }
+
+    fn to_repo_url_with_branch(&self) -> Result<String, GrowseError> {
+        let base_url = self.config.base_url(self.url)?;
+        let owner = self
+            .url
+            .owner
+            .clone()
+            .ok_or(GrowseError::MissingRemotePart("owner"))?;
+
+        let branch_name = self.at_ref();
         let name = self.url.name.clone();
-        let path = self.state.path.clone().ok_or("No path found")?;
 
         let new_url = Url::parse_with_params(
-            &format!("https://{host}/projects/{owner}/repos/{name}/browse/{path}"),
+            &format!("{base_url}/projects/{owner}/repos/{name}/browse"),
             &[("at", branch_name.as_str())],
         )?;
 
         Ok(new_url.to_string())
     }
 
-    fn to_repo_url_with_path(&self) -> Result<String, Box<dyn std::error::Error>> {
+    fn to_pull_request_url(&self) -> Result<String, GrowseError> {
         let repo_url = self.to_repo_url()?;
-        let path = self.state.path.clone().ok_or("No path found")?;
-        Ok(format!("{repo_url}/browse/{path}"))
+        let branch_name = format!("refs/heads/{}", self.state.branch);
+
+        let mut new_url = Url::parse(&format!("{repo_url}/pull-requests?create"))?;
+        new_url
+            .query_pairs_mut()
+            .append_pair("sourceBranch", &branch_name);
+
+        Ok(new_url.to_string())
     }
 
-    fn to_repo_url_with_path_and_branch_and_line_number(
-        &self,
-    ) -> Result<String, Box<dyn std::error::Error>> {
-        let host = self.url.host.clone().ok_or("No host found")?;
-        let owner = self.url.owner.clone().ok_or("No owner found")?;
+    fn to_commit_url(&self) -> Result<String, GrowseError> {
+        let repo_url = self.to_repo_url()?;
+        let commit = self.state.commit()?;
 
-        let branch_name = format!("refs/heads/{}", self.state.branch);
-        let name = self.url.name.clone();
-        let path = self.state.path.clone().ok_or("No path found")?;
-        let line_number = self.state.line_number.ok_or("No line number found")?;
+        Ok(format!("{repo_url}/commits/{commit}"))
+    }
 
-        let mut new_url = Url::parse_with_params(
-            &format!("https://{host}/projects/{owner}/repos/{name}/browse/{path}"),
-            &[("at", branch_name.as_str())],
+    fn to_compare_url(&self) -> Result<String, GrowseError> {
+        let repo_url = self.to_repo_url()?;
+        let (base, head) = self.state.compare()?;
+
+        let new_url = Url::parse_with_params(
+            &format!("{repo_url}/compare/diff"),
+            &[("sourceBranch", head), ("targetBranch", base)],
         )?;
-        new_url.set_fragment(Some(line_number.to_string().as_str()));
 
         Ok(new_url.to_string())
     }
 
-    fn to_repo_url_with_path_and_line_number(&self) -> Result<String, Box<dyn std::error::Error>> {
-        let host = self.url.host.clone().ok_or("No host found")?;
-        let owner = self.url.owner.clone().ok_or("No owner found")?;
-
-        let name = self.url.name.clone();
-        let path = self.state.path.clone().ok_or("No path found")?;
-        let line_number = self.state.line_number.ok_or("No line number found")?;
+    fn to_blame_url(&self) -> Result<String, GrowseError> {
+        let branch_name = self.at_ref();
 
-        let mut new_url = Url::parse(&format!(
-            "https://{host}/projects/{owner}/repos/{name}/browse/{path}"
-        ))?;
-        new_url.set_fragment(Some(line_number.to_string().as_str()));
+        let mut new_url = self.file_url("browse")?;
+        new_url
+            .query_pairs_mut()
+            .append_pair("at", branch_name.as_str())
+            .append_pair("blame", "true");
+        if self.state.line_number.is_some() {
+            let fragment = self.line_fragment()?;
+            new_url.set_fragment(Some(fragment.as_str()));
+        }
 
         Ok(new_url.to_string())
     }
 
-    fn to_repo_url_with_branch(&self) -> Result<String, Box<dyn std::error::Error>> {
-        let host = self.url.host.clone().ok_or("No host found")?;
-        let owner = self.url.owner.clone().ok_or("No owner found")?;
-
-        let branch_name = format!("refs/heads/{}", self.state.branch);
-        let name = self.url.name.clone();
+    fn to_history_url(&self) -> Result<String, GrowseError> {
+        let repo_url = self.to_repo_url()?;
+        let branch_name = self.at_ref();
 
-        let new_url = Url::parse_with_params(
-            &format!("https://{host}/projects/{owner}/repos/{name}/browse"),
-            &[("at", branch_name.as_str())],
+        let mut new_url = Url::parse_with_params(
+            &format!("{repo_url}/commits"),
+            &[("until", branch_name.as_str())],
         )?;
+        if let Some(path) = &self.state.path {
+            new_url.query_pairs_mut().append_pair("path", path);
+        }
+
+        Ok(new_url.to_string())
+    }
+
+    fn to_raw_url(&self) -> Result<String, GrowseError> {
+        let branch_name = self.at_ref();
+
+        let mut new_url = self.file_url("raw")?;
+        new_url
+            .query_pairs_mut()
+            .append_pair("at", branch_name.as_str());
 
         Ok(new_url.to_string())
     }
 
-    fn to_repo_url(&self) -> Result<String, Box<dyn std::error::Error>> {
-        let host = self.url.host.clone().ok_or("No host found")?;
-        let owner = self.url.owner.clone().ok_or("No owner found")?;
+    fn to_repo_url(&self) -> Result<String, GrowseError> {
+        let base_url = self.config.base_url(self.url)?;
+        let owner = self
+            .url
+            .owner
+            .clone()
+            .ok_or(GrowseError::MissingRemotePart("owner"))?;
         let name = self.url.name.clone();
 
-        Ok(format!("https://{host}/projects/{owner}/repos/{name}"))
+        Ok(format!("{base_url}/projects/{owner}/repos/{name}"))
+    }
+}
+
+impl BitBucket<'_> {
+    // <repo>/<view>/<path> with each segment of the path percent-encoded, a
+    // # or ? in a file name would otherwise cut the path short
+    fn file_url(&self, view: &str) -> Result<Url, GrowseError> {
+        let path = self.state.path()?;
+        let mut new_url = Url::parse(&self.to_repo_url()?)?;
+        new_url
+            .path_segments_mut()
+            .expect("the repository url is an http(s) url")
+            .push(view)
+            .extend(path.split('/'));
+
+        Ok(new_url)
+    }
+
+    // Bitbucket Server takes either a fully qualified ref or a commit id for `at`
+    fn at_ref(&self) -> String {
+        match &self.state.commit {
+            Some(commit) => commit.clone(),
+            None => format!("refs/heads/{}", self.state.branch),
+        }
+    }
+
+    // Bitbucket Server highlights ranges as #10-25
+    fn line_fragment(&self) -> Result<String, GrowseError> {
+        let line_number = self.state.line_number()?;
+        match self.state.end_line_number {
+            Some(end_line_number) => Ok(format!("{line_number}-{end_line_number}")),
+            None => Ok(line_number.to_string()),
+        }
     }
 }
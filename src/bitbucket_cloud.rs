@@ -0,0 +1,147 @@
+use crate::*;
+
+// bitbucket.org, the Bitbucket Server/Data Center URL layout lives in bitbucket.rs
+pub struct BitBucketCloud<'a> {
+    url: &'a GitUrl,
+    config: &'a GrowseConfig,
+    state: &'a GrowseState,
+}
+
+pub struct BitBucketCloudProvider;
+
+impl Provider for BitBucketCloudProvider {
+    fn backends(&self) -> &[&str] {
+        &["bitbucket-cloud"]
+    }
+
+    fn confidence(&self, url: &GitUrl, _config: &GrowseConfig) -> Confidence {
+        match url.host.as_deref() {
+            Some("bitbucket.org") => Confidence::Known,
+            _ => Confidence::No,
+        }
+    }
+
+    fn repo<'a>(
+        &self,
+        url: &'a GitUrl,
+        config: &'a GrowseConfig,
+        state: &'a GrowseState,
+    ) -> Box<dyn Repo + 'a> {
+        Box::new(BitBucketCloud { url, config, state })
+    }
+}
+
+impl Repo for BitBucketCloud<'_> {
+    fn name(&self) -> String {
+        "Bitbucket Cloud".to_string()
+    }
+
+    fn to_repo_url_with_path_and_branch(&self) -> Result<String, GrowseError> {
+        self.to_repo_url_with_path()
+    }
+
+    fn to_repo_url_with_path(&self) -> Result<String, GrowseError> {
+        let url = self.to_repo_url_with_branch()?;
+        let path = encode(self.state.path()?);
+
+        Ok(format!("{url}/{path}"))
+    }
+
+    fn to_repo_url_with_path_and_branch_and_line_number(&self) -> Result<String, GrowseError> {
+        self.to_repo_url_with_path_and_line_number()
+    }
+
+    fn to_repo_url_with_path_and_line_number(&self) -> Result<String, GrowseError> {
+        let url = self.to_repo_url_with_path()?;
+        let fragment = self.line_fragment()?;
+
+        Ok(format!("{url}#{fragment}"))
+    }
+
+    fn to_repo_url_with_branch(&self) -> Result<String, GrowseError> {
+        let repo_url = self.to_repo_url()?;
+        let git_ref = encode(self.state.git_ref());
+
+        Ok(format!("{repo_url}/src/{git_ref}"))
+    }
+
+    fn to_pull_request_url(&self) -> Result<String, GrowseError> {
+        let repo_url = self.to_repo_url()?;
+        let new_url = Url::parse_with_params(
+            &format!("{repo_url}/pull-requests/new"),
+            &[("source", self.state.branch.as_str())],
+        )?;
+
+        Ok(new_url.to_string())
+    }
+
+    fn to_commit_url(&self) -> Result<String, GrowseError> {
+        let repo_url = self.to_repo_url()?;
+        let commit = self.state.commit()?;
+
+        Ok(format!("{repo_url}/commits/{commit}"))
+    }
+
+    fn to_compare_url(&self) -> Result<String, GrowseError> {
+        let repo_url = self.to_repo_url()?;
+        let (base, head) = self.state.compare()?;
+        let base = encode(short_ref(base));
+        let head = encode(short_ref(head));
+
+        // source and destination are separated by an encoded carriage return
+        Ok(format!("{repo_url}/branches/compare/{head}%0D{base}"))
+    }
+
+    fn to_blame_url(&self) -> Result<String, GrowseError> {
+        let repo_url = self.to_repo_url()?;
+        let git_ref = encode(self.state.git_ref());
+        let path = encode(self.state.path()?);
+        let url = format!("{repo_url}/annotate/{git_ref}/{path}");
+
+        if self.state.line_number.is_some() {
+            let fragment = self.line_fragment()?;
+            Ok(format!("{url}#{fragment}"))
+        } else {
+            Ok(url)
+        }
+    }
+
+    fn to_history_url(&self) -> Result<String, GrowseError> {
+        let repo_url = self.to_repo_url()?;
+        let git_ref = encode(self.state.git_ref());
+
+        match &self.state.path {
+            Some(path) => Ok(format!(
+                "{repo_url}/history-node/{git_ref}/{}",
+                encode(path)
+            )),
+            None => Ok(format!("{repo_url}/commits/branch/{git_ref}")),
+        }
+    }
+
+    fn to_raw_url(&self) -> Result<String, GrowseError> {
+        let repo_url = self.to_repo_url()?;
+        let git_ref = encode(self.state.git_ref());
+        let path = encode(self.state.path()?);
+
+        Ok(format!("{repo_url}/raw/{git_ref}/{path}"))
+    }
+
+    fn to_repo_url(&self) -> Result<String, GrowseError> {
+        let base_url = self.config.base_url(self.url)?;
+        let fullname = &self.url.fullname;
+
+        Ok(format!("{base_url}/{fullname}"))
+    }
+}
+
+impl BitBucketCloud<'_> {
+    // Bitbucket Cloud highlights ranges as #lines-10:25
+    fn line_fragment(&self) -> Result<String, GrowseError> {
+        let line_number = self.state.line_number()?;
+        match self.state.end_line_number {
+            Some(end_line_number) => Ok(format!("lines-{line_number}:{end_line_number}")),
+            None => Ok(format!("lines-{line_number}")),
+        }
+    }
+}
@@ -0,0 +1,114 @@
+use crate::*;
+
+pub struct Cgit<'a> {
+    url: &'a GitUrl,
+    config: &'a GrowseConfig,
+    state: &'a GrowseState,
+}
+
+pub struct CgitProvider;
+
+impl Provider for CgitProvider {
+    fn backends(&self) -> &[&str] {
+        &["cgit"]
+    }
+
+    fn confidence(&self, _url: &GitUrl, _config: &GrowseConfig) -> Confidence {
+        Confidence::No
+    }
+
+    fn repo<'a>(
+        &self,
+        url: &'a GitUrl,
+        config: &'a GrowseConfig,
+        state: &'a GrowseState,
+    ) -> Box<dyn Repo + 'a> {
+        Box::new(Cgit { url, config, state })
+    }
+}
+
+impl Repo for Cgit<'_> {
+    fn name(&self) -> String {
+        "cgit".to_string()
+    }
+
+    fn to_repo_url_with_path_and_branch(&self) -> Result<String, GrowseError> {
+        self.to_repo_url_with_path()
+    }
+
+    fn to_repo_url_with_path(&self) -> Result<String, GrowseError> {
+        self.view_url("tree")
+    }
+
+    fn to_repo_url_with_path_and_branch_and_line_number(&self) -> Result<String, GrowseError> {
+        self.to_repo_url_with_path_and_line_number()
+    }
+
+    fn to_repo_url_with_path_and_line_number(&self) -> Result<String, GrowseError> {
+        let url = self.to_repo_url_with_path()?;
+        let line_number = self.state.line_number()?;
+
+        Ok(format!("{url}#n{line_number}"))
+    }
+
+    fn to_repo_url_with_branch(&self) -> Result<String, GrowseError> {
+        self.view_url("tree")
+    }
+
+    fn to_commit_url(&self) -> Result<String, GrowseError> {
+        let repo_url = self.to_repo_url()?;
+        let commit = self.state.commit()?;
+        let new_url = Url::parse_with_params(&format!("{repo_url}/commit/"), &[("id", commit)])?;
+
+        Ok(new_url.to_string())
+    }
+
+    fn to_compare_url(&self) -> Result<String, GrowseError> {
+        let repo_url = self.to_repo_url()?;
+        let (base, head) = self.state.compare()?;
+        let new_url =
+            Url::parse_with_params(&format!("{repo_url}/diff/"), &[("id", head), ("id2", base)])?;
+
+        Ok(new_url.to_string())
+    }
+
+    fn to_blame_url(&self) -> Result<String, GrowseError> {
+        let url = self.view_url("blame")?;
+
+        match self.state.line_number {
+            Some(line_number) => Ok(format!("{url}#n{line_number}")),
+            None => Ok(url),
+        }
+    }
+
+    fn to_history_url(&self) -> Result<String, GrowseError> {
+        self.view_url("log")
+    }
+
+    fn to_raw_url(&self) -> Result<String, GrowseError> {
+        self.view_url("plain")
+    }
+
+    fn to_repo_url(&self) -> Result<String, GrowseError> {
+        let base_url = self.config.base_url(self.url)?;
+        // cgit repositories are usually named after their directory, .git suffix included
+        let repo = self.url.path.trim_matches('/');
+
+        Ok(format!("{base_url}/{repo}"))
+    }
+}
+
+impl Cgit<'_> {
+    // <repo>/<view>/<path>?h=<branch> or ?id=<commit>
+    fn view_url(&self, view: &str) -> Result<String, GrowseError> {
+        let repo_url = self.to_repo_url()?;
+        let path = encode(self.state.path.as_deref().unwrap_or_default());
+        let rev = match &self.state.commit {
+            Some(commit) => ("id", commit.as_str()),
+            None => ("h", self.state.branch.as_str()),
+        };
+        let new_url = Url::parse_with_params(&format!("{repo_url}/{view}/{path}"), &[rev])?;
+
+        Ok(new_url.to_string())
+    }
+}
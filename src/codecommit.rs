@@ -0,0 +1,159 @@
+use crate::*;
+
+// AWS CodeCommit has no web UI of its own, links go to the AWS console.
+pub struct CodeCommit<'a> {
+    url: &'a GitUrl,
+    state: &'a GrowseState,
+}
+
+pub struct CodeCommitProvider;
+
+impl Provider for CodeCommitProvider {
+    fn backends(&self) -> &[&str] {
+        &["codecommit"]
+    }
+
+    fn confidence(&self, url: &GitUrl, _config: &GrowseConfig) -> Confidence {
+        match url.host.as_deref() {
+            Some(host)
+                if host.starts_with("git-codecommit.") && host.ends_with(".amazonaws.com") =>
+            {
+                Confidence::Known
+            }
+            _ => Confidence::No,
+        }
+    }
+
+    fn repo<'a>(
+        &self,
+        url: &'a GitUrl,
+        _config: &'a GrowseConfig,
+        state: &'a GrowseState,
+    ) -> Box<dyn Repo + 'a> {
+        Box::new(CodeCommit { url, state })
+    }
+}
+
+impl Repo for CodeCommit<'_> {
+    fn name(&self) -> String {
+        "CodeCommit".to_string()
+    }
+
+    fn to_repo_url_with_path_and_branch(&self) -> Result<String, GrowseError> {
+        self.to_repo_url_with_path()
+    }
+
+    fn to_repo_url_with_path(&self) -> Result<String, GrowseError> {
+        let path = self.state.path()?;
+        let git_ref = self.qualified_ref();
+
+        self.console_url(&format!("browse/{git_ref}/--/{path}"))
+    }
+
+    fn to_repo_url_with_path_and_branch_and_line_number(&self) -> Result<String, GrowseError> {
+        self.to_repo_url_with_path_and_line_number()
+    }
+
+    fn to_repo_url_with_path_and_line_number(&self) -> Result<String, GrowseError> {
+        let mut new_url = Url::parse(&self.to_repo_url_with_path()?)?;
+        let line_number = self.state.line_number()?;
+        let end_line_number = self.state.end_line_number.unwrap_or(line_number);
+        new_url
+            .query_pairs_mut()
+            .append_pair("lines", &format!("{line_number}-{end_line_number}"));
+
+        Ok(new_url.to_string())
+    }
+
+    fn to_repo_url_with_branch(&self) -> Result<String, GrowseError> {
+        let git_ref = self.qualified_ref();
+
+        self.console_url(&format!("browse/{git_ref}"))
+    }
+
+    fn to_commit_url(&self) -> Result<String, GrowseError> {
+        let commit = self.state.commit()?;
+
+        self.console_url(&format!("commit/{commit}"))
+    }
+
+    fn to_compare_url(&self) -> Result<String, GrowseError> {
+        let (base, head) = self.state.compare()?;
+
+        self.console_url(&format!("compare/{base}/.../{head}"))
+    }
+
+    fn to_repo_url(&self) -> Result<String, GrowseError> {
+        self.console_url("browse")
+    }
+}
+
+impl CodeCommit<'_> {
+    // git-codecommit.<region>.amazonaws.com
+    fn region(&self) -> Result<String, GrowseError> {
+        let host = self
+            .url
+            .host
+            .as_ref()
+            .ok_or(GrowseError::MissingRemotePart("host"))?;
+        let region = host
+            .split('.')
+            .nth(1)
+            .ok_or(GrowseError::MissingRemotePart("region"))?;
+        Ok(region.to_string())
+    }
+
+    // each segment of the view is percent-encoded, so a # or ? in a file name
+    // stays part of the path
+    fn console_url(&self, view: &str) -> Result<String, GrowseError> {
+        let region = self.region()?;
+        let name = &self.url.name;
+        let mut new_url = Url::parse_with_params(
+            &format!(
+                "https://{region}.console.aws.amazon.com/codesuite/codecommit/repositories/{name}"
+            ),
+            &[("region", region.as_str())],
+        )?;
+        new_url
+            .path_segments_mut()
+            .expect("the console url is an https url")
+            .extend(view.split('/'));
+
+        Ok(new_url.to_string())
+    }
+
+    fn qualified_ref(&self) -> String {
+        match &self.state.commit {
+            Some(commit) => commit.clone(),
+            None => format!("refs/heads/{}", self.state.branch),
+        }
+    }
+}
+
+// The git-remote-codecommit helper uses codecommit::<region>://[<profile>@]<repo>,
+// which GitUrl can't parse, so it's rewritten to the equivalent HTTPS remote.
+pub fn normalize_grc_url(url: &str) -> Result<String, GrowseError> {
+    let Some(rest) = url.strip_prefix("codecommit:") else {
+        return Ok(url.to_string());
+    };
+    let (region, repo) = rest
+        .split_once("//")
+        .ok_or_else(|| GrowseError::InvalidRemote {
+            remote: url.to_string(),
+            reason: "expected codecommit::<region>://<repo>".to_string(),
+        })?;
+    let region = match region.trim_matches(':') {
+        "" => std::env::var("AWS_REGION")
+            .or_else(|_| std::env::var("AWS_DEFAULT_REGION"))
+            .map_err(|_| GrowseError::InvalidRemote {
+                remote: url.to_string(),
+                reason: "no region in the remote and AWS_REGION is not set".to_string(),
+            })?,
+        region => region.to_string(),
+    };
+    let repo = repo.rsplit('@').next().unwrap_or(repo);
+
+    Ok(format!(
+        "https://git-codecommit.{region}.amazonaws.com/v1/repos/{repo}"
+    ))
+}
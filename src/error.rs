@@ -0,0 +1,244 @@
+use std::fmt;
+use std::path::PathBuf;
+
+/// Why no link could be built, see [`GrowseError::exit_code`] for how the
+/// command line reports each one.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum GrowseError {
+    /// No backend matched the remote, host is None for local and file:// remotes.
+    UnknownHost {
+        /// The remote url as configured in git.
+        remote: String,
+        /// Host of the remote url.
+        host: Option<String>,
+    },
+    /// Backend set for a host in growse.toml that no provider is registered for.
+    UnknownBackend {
+        /// Host of the `[hosts]` entry.
+        host: String,
+        /// The configured backend.
+        backend: String,
+    },
+    /// Remote url git-url-parse can't make sense of.
+    InvalidRemote {
+        /// The remote url as configured in git.
+        remote: String,
+        /// Parse error.
+        reason: String,
+    },
+    /// Part of the remote url a backend needs to build its links, e.g. the owner.
+    MissingRemotePart(&'static str),
+    /// The backend has no page for the requested kind of link.
+    Unsupported {
+        /// Name of the backend.
+        backend: String,
+        /// Kind of link, e.g. `blame`.
+        link: String,
+    },
+    /// Input a link kind needs but wasn't given, e.g. the path for a blame link.
+    MissingState(&'static str),
+    /// A Gerrit change was requested but HEAD has no Change-Id trailer.
+    NoChangeId,
+    /// Not inside a git repository, or one git can't open.
+    NoRepository(git2::Error),
+    /// Revision that doesn't name a commit, e.g. for --show or --compare.
+    InvalidRevision {
+        /// The revision as given.
+        rev: String,
+        /// Error resolving the revision.
+        source: git2::Error,
+    },
+    /// Named remote or, with None, any remote at all.
+    NoRemote(Option<String>),
+    /// A link to the current branch was requested on a detached HEAD.
+    DetachedHead,
+    /// File path that isn't inside the work tree.
+    PathOutsideRepo(String),
+    /// Path argument that can't be split into a path and line numbers.
+    InvalidPath {
+        /// The path argument as given.
+        path: String,
+        /// What's wrong with it.
+        reason: String,
+    },
+    /// Config file given on the command line that doesn't exist.
+    ConfigNotFound(PathBuf),
+    /// Config file that exists but can't be read.
+    ConfigRead {
+        /// Path of the config file.
+        path: PathBuf,
+        /// Error reading it.
+        source: std::io::Error,
+    },
+    /// Config file that isn't valid TOML or has unknown settings.
+    Config {
+        /// Path of the config file.
+        path: PathBuf,
+        /// Error parsing it.
+        source: toml::de::Error,
+    },
+    /// URL template that is missing or uses an unknown placeholder.
+    InvalidTemplate(String),
+    /// Name of a git object, e.g. a branch, that isn't valid UTF-8.
+    InvalidUtf8(&'static str),
+    /// The browser couldn't be opened.
+    OpenLink {
+        /// The link that was built.
+        url: String,
+        /// Error opening it.
+        source: std::io::Error,
+    },
+    /// Any other git error.
+    Git(git2::Error),
+    /// Any other IO error.
+    Io(std::io::Error),
+    /// The link built by a backend isn't a valid url.
+    Url(url::ParseError),
+}
+
+impl GrowseError {
+    /// Error from opening a repository, missing repositories are told apart
+    /// from other git errors.
+    pub fn from_open(e: git2::Error) -> Self {
+        match (e.code(), e.class()) {
+            (git2::ErrorCode::NotFound, _) | (_, git2::ErrorClass::Repository) => {
+                GrowseError::NoRepository(e)
+            }
+            _ => GrowseError::Git(e),
+        }
+    }
+
+    /// The backend has no `link` links.
+    pub fn unsupported(backend: &str, link: &str) -> Self {
+        GrowseError::Unsupported {
+            backend: backend.to_string(),
+            link: link.to_string(),
+        }
+    }
+
+    /// Process exit code, grouped so scripts can tell why no link was opened:
+    /// 2 usage, 3 config, 4 repository state, 5 remote, 6 link not available.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            GrowseError::InvalidPath { .. } | GrowseError::InvalidRevision { .. } => 2,
+            GrowseError::ConfigNotFound(_)
+            | GrowseError::ConfigRead { .. }
+            | GrowseError::Config { .. }
+            | GrowseError::UnknownBackend { .. }
+            | GrowseError::InvalidTemplate(_) => 3,
+            GrowseError::NoRepository(_)
+            | GrowseError::NoRemote(_)
+            | GrowseError::DetachedHead
+            | GrowseError::PathOutsideRepo(_)
+            | GrowseError::NoChangeId => 4,
+            GrowseError::UnknownHost { .. }
+            | GrowseError::InvalidRemote { .. }
+            | GrowseError::MissingRemotePart(_) => 5,
+            GrowseError::Unsupported { .. } | GrowseError::MissingState(_) => 6,
+            GrowseError::InvalidUtf8(_)
+            | GrowseError::OpenLink { .. }
+            | GrowseError::Git(_)
+            | GrowseError::Io(_)
+            | GrowseError::Url(_) => 1,
+        }
+    }
+}
+
+impl fmt::Display for GrowseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GrowseError::UnknownHost { remote, host: None } => write!(
+                f,
+                "Remote {remote} has no host, only remotes on a git hosting service can be opened"
+            ),
+            GrowseError::UnknownHost {
+                remote,
+                host: Some(host),
+            } => write!(
+                f,
+                "Could not detect the hosting service of {host} for remote {remote}, \
+                set its backend in growse.toml:\n\n\
+                [hosts.\"{host}\"]\n\
+                backend = \"github\"\n\n\
+                or enable the generic fallback to open https://{host}/<owner>/<repo>:\n\n\
+                [growse]\n\
+                generic_fallback = true"
+            ),
+            GrowseError::UnknownBackend { host, backend } => {
+                write!(f, "Unknown backend {backend} set for {host} in growse.toml")
+            }
+            GrowseError::InvalidRemote { remote, reason } => {
+                write!(f, "Invalid remote url {remote}: {reason}")
+            }
+            GrowseError::MissingRemotePart(part) => write!(f, "No {part} found in the remote url"),
+            GrowseError::Unsupported { backend, link } => {
+                write!(f, "{backend} has no {link} links")
+            }
+            GrowseError::MissingState(what) => write!(f, "No {what} found"),
+            GrowseError::NoChangeId => {
+                write!(f, "No Change-Id trailer found in the HEAD commit message")
+            }
+            GrowseError::NoRepository(e) => write!(f, "Not in a git repository: {}", e.message()),
+            GrowseError::InvalidRevision { rev, source } => {
+                write!(f, "Invalid revision {rev}: {}", source.message())
+            }
+            GrowseError::NoRemote(None) => write!(f, "No remotes found"),
+            GrowseError::NoRemote(Some(name)) => write!(f, "Remote {name} not found"),
+            GrowseError::DetachedHead => write!(f, "HEAD is detached, no current branch"),
+            GrowseError::PathOutsideRepo(path) => {
+                write!(f, "{path} is outside the repository work tree")
+            }
+            GrowseError::InvalidPath { path, reason } => write!(f, "Invalid path {path}: {reason}"),
+            GrowseError::ConfigNotFound(path) => {
+                write!(f, "Config file {} not found", path.display())
+            }
+            GrowseError::ConfigRead { path, source } => {
+                write!(f, "Could not read config file {}: {source}", path.display())
+            }
+            GrowseError::Config { path, source } => {
+                write!(f, "Invalid config file {}: {source}", path.display())
+            }
+            GrowseError::InvalidTemplate(reason) => write!(f, "Invalid url template: {reason}"),
+            GrowseError::InvalidUtf8(what) => write!(f, "{what} is not valid UTF-8"),
+            GrowseError::OpenLink { url, source } => write!(f, "Could not open {url}: {source}"),
+            GrowseError::Git(e) => write!(f, "{e}"),
+            GrowseError::Io(e) => write!(f, "{e}"),
+            GrowseError::Url(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for GrowseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            GrowseError::NoRepository(e) => Some(e),
+            GrowseError::InvalidRevision { source, .. } => Some(source),
+            GrowseError::ConfigRead { source, .. } => Some(source),
+            GrowseError::Config { source, .. } => Some(source),
+            GrowseError::OpenLink { source, .. } => Some(source),
+            GrowseError::Git(e) => Some(e),
+            GrowseError::Io(e) => Some(e),
+            GrowseError::Url(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<git2::Error> for GrowseError {
+    fn from(e: git2::Error) -> Self {
+        GrowseError::Git(e)
+    }
+}
+
+impl From<std::io::Error> for GrowseError {
+    fn from(e: std::io::Error) -> Self {
+        GrowseError::Io(e)
+    }
+}
+
+impl From<url::ParseError> for GrowseError {
+    fn from(e: url::ParseError) -> Self {
+        GrowseError::Url(e)
+    }
+}
@@ -0,0 +1,75 @@
+use crate::*;
+
+// Fallback for hosts no backend recognises, only the repository page can be guessed.
+pub struct Generic<'a> {
+    url: &'a GitUrl,
+    config: &'a GrowseConfig,
+}
+
+pub struct GenericProvider;
+
+impl Provider for GenericProvider {
+    fn backends(&self) -> &[&str] {
+        &["generic"]
+    }
+
+    fn confidence(&self, url: &GitUrl, config: &GrowseConfig) -> Confidence {
+        if config.generic_fallback && url.host.is_some() {
+            Confidence::Fallback
+        } else {
+            Confidence::No
+        }
+    }
+
+    fn repo<'a>(
+        &self,
+        url: &'a GitUrl,
+        config: &'a GrowseConfig,
+        _state: &'a GrowseState,
+    ) -> Box<dyn Repo + 'a> {
+        Box::new(Generic { url, config })
+    }
+}
+
+impl Repo for Generic<'_> {
+    fn name(&self) -> String {
+        format!(
+            "The generic backend for {}",
+            self.url.host.as_deref().unwrap_or_default()
+        )
+    }
+
+    fn to_repo_url_with_path_and_branch(&self) -> Result<String, GrowseError> {
+        Err(self.unsupported("file"))
+    }
+
+    fn to_repo_url_with_path(&self) -> Result<String, GrowseError> {
+        Err(self.unsupported("file"))
+    }
+
+    fn to_repo_url_with_path_and_branch_and_line_number(&self) -> Result<String, GrowseError> {
+        Err(self.unsupported("file"))
+    }
+
+    fn to_repo_url_with_path_and_line_number(&self) -> Result<String, GrowseError> {
+        Err(self.unsupported("file"))
+    }
+
+    fn to_repo_url_with_branch(&self) -> Result<String, GrowseError> {
+        Err(self.unsupported("branch"))
+    }
+
+    fn to_repo_url(&self) -> Result<String, GrowseError> {
+        let base_url = self.config.base_url(self.url)?;
+        let fullname = &self.url.fullname;
+
+        Ok(format!("{base_url}/{fullname}"))
+    }
+}
+
+impl Generic<'_> {
+    // only the repository page is known, a backend has to be configured for the rest
+    fn unsupported(&self, kind: &str) -> GrowseError {
+        GrowseError::unsupported(&self.name(), kind)
+    }
+}
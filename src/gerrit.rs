@@ -0,0 +1,174 @@
+use crate::*;
+
+// Gerrit code review, browsed through its Gitiles plugin or a standalone Gitiles host.
+pub struct Gerrit<'a> {
+    url: &'a GitUrl,
+    config: &'a GrowseConfig,
+    state: &'a GrowseState,
+}
+
+pub struct GerritProvider;
+
+impl Provider for GerritProvider {
+    fn backends(&self) -> &[&str] {
+        &["gerrit", "gitiles"]
+    }
+
+    fn confidence(&self, url: &GitUrl, _config: &GrowseConfig) -> Confidence {
+        match url.host.as_deref() {
+            None => Confidence::No,
+            // default ssh port of Gerrit
+            Some(host) if host.ends_with("googlesource.com") || url.port == Some(29418) => {
+                Confidence::Known
+            }
+            Some(host) => Confidence::mentioned(host, "gerrit"),
+        }
+    }
+
+    fn repo<'a>(
+        &self,
+        url: &'a GitUrl,
+        config: &'a GrowseConfig,
+        state: &'a GrowseState,
+    ) -> Box<dyn Repo + 'a> {
+        Box::new(Gerrit { url, config, state })
+    }
+}
+
+impl Repo for Gerrit<'_> {
+    fn name(&self) -> String {
+        "Gerrit".to_string()
+    }
+
+    fn to_repo_url_with_path_and_branch(&self) -> Result<String, GrowseError> {
+        self.to_repo_url_with_path()
+    }
+
+    fn to_repo_url_with_path(&self) -> Result<String, GrowseError> {
+        let url = self.to_repo_url_with_branch()?;
+        let path = encode(self.state.path()?);
+
+        Ok(format!("{url}/{path}"))
+    }
+
+    fn to_repo_url_with_path_and_branch_and_line_number(&self) -> Result<String, GrowseError> {
+        self.to_repo_url_with_path_and_line_number()
+    }
+
+    fn to_repo_url_with_path_and_line_number(&self) -> Result<String, GrowseError> {
+        let url = self.to_repo_url_with_path()?;
+        // Gitiles only anchors single lines, ranges start at the first line
+        let line_number = self.state.line_number()?;
+
+        Ok(format!("{url}#{line_number}"))
+    }
+
+    fn to_repo_url_with_branch(&self) -> Result<String, GrowseError> {
+        let repo_url = self.to_repo_url()?;
+        let git_ref = self.qualified_ref();
+
+        Ok(format!("{repo_url}/+/{git_ref}"))
+    }
+
+    fn to_pull_request_url(&self) -> Result<String, GrowseError> {
+        let change_id = self
+            .state
+            .change_id
+            .clone()
+            .ok_or(GrowseError::NoChangeId)?;
+        let review_url = self.review_url()?;
+
+        Ok(format!("{review_url}/q/{change_id}"))
+    }
+
+    fn to_commit_url(&self) -> Result<String, GrowseError> {
+        let repo_url = self.to_repo_url()?;
+        let commit = self.state.commit()?;
+
+        Ok(format!("{repo_url}/+/{commit}"))
+    }
+
+    fn to_compare_url(&self) -> Result<String, GrowseError> {
+        let repo_url = self.to_repo_url()?;
+        let (base, head) = self.state.compare()?;
+        let base = encode(base);
+        let head = encode(head);
+
+        Ok(format!("{repo_url}/+/{base}..{head}"))
+    }
+
+    fn to_blame_url(&self) -> Result<String, GrowseError> {
+        let repo_url = self.to_repo_url()?;
+        let git_ref = self.qualified_ref();
+        let path = encode(self.state.path()?);
+        let url = format!("{repo_url}/+blame/{git_ref}/{path}");
+
+        match self.state.line_number {
+            Some(line_number) => Ok(format!("{url}#{line_number}")),
+            None => Ok(url),
+        }
+    }
+
+    fn to_history_url(&self) -> Result<String, GrowseError> {
+        let repo_url = self.to_repo_url()?;
+        let git_ref = self.qualified_ref();
+        let url = format!("{repo_url}/+log/{git_ref}");
+
+        match &self.state.path {
+            Some(path) => Ok(format!("{url}/{}", encode(path))),
+            None => Ok(url),
+        }
+    }
+
+    fn to_raw_url(&self) -> Result<String, GrowseError> {
+        // Gitiles serves file content base64 encoded
+        let url = self.to_repo_url_with_path()?;
+
+        Ok(format!("{url}?format=TEXT"))
+    }
+
+    fn to_repo_url(&self) -> Result<String, GrowseError> {
+        let base_url = self.config.base_url(self.url)?;
+        let project = self.url.path.trim_matches('/');
+        let project = project.strip_suffix(".git").unwrap_or(project);
+        // authenticated HTTP remotes are prefixed with /a/
+        let project = project.strip_prefix("a/").unwrap_or(project);
+
+        if self.is_standalone_gitiles() {
+            Ok(format!("{base_url}/{project}"))
+        } else {
+            Ok(format!("{base_url}/plugins/gitiles/{project}"))
+        }
+    }
+}
+
+impl Gerrit<'_> {
+    fn is_standalone_gitiles(&self) -> bool {
+        let host = self.url.host.as_deref();
+        self.config.backend_for(host) == Some("gitiles")
+            || host.is_some_and(|host| host.ends_with("googlesource.com"))
+    }
+
+    // Gitiles needs fully qualified refs, commits are used as is
+    fn qualified_ref(&self) -> String {
+        match &self.state.commit {
+            Some(commit) => commit.clone(),
+            None => format!("refs/heads/{}", encode(&self.state.branch)),
+        }
+    }
+
+    // googlesource.com serves reviews for <name>.googlesource.com from <name>-review.googlesource.com
+    fn review_url(&self) -> Result<String, GrowseError> {
+        let host = self
+            .url
+            .host
+            .clone()
+            .ok_or(GrowseError::MissingRemotePart("host"))?;
+        match host.strip_suffix(".googlesource.com") {
+            Some(name) if !name.ends_with("-review") => {
+                Ok(format!("https://{name}-review.googlesource.com"))
+            }
+            _ => self.config.base_url(self.url),
+        }
+    }
+}
@@ -0,0 +1,146 @@
+use crate::*;
+
+// Gitea and its fork Forgejo (which runs codeberg.org) share the same URL layout.
+pub struct Gitea<'a> {
+    url: &'a GitUrl,
+    config: &'a GrowseConfig,
+    state: &'a GrowseState,
+}
+
+pub struct GiteaProvider;
+
+impl Provider for GiteaProvider {
+    fn backends(&self) -> &[&str] {
+        &["gitea", "forgejo"]
+    }
+
+    fn confidence(&self, url: &GitUrl, _config: &GrowseConfig) -> Confidence {
+        match url.host.as_deref() {
+            None => Confidence::No,
+            Some("codeberg.org") => Confidence::Known,
+            Some(host) => ["gitea", "forgejo", "codeberg"]
+                .into_iter()
+                .map(|name| Confidence::mentioned(host, name))
+                .max()
+                .unwrap_or(Confidence::No),
+        }
+    }
+
+    fn repo<'a>(
+        &self,
+        url: &'a GitUrl,
+        config: &'a GrowseConfig,
+        state: &'a GrowseState,
+    ) -> Box<dyn Repo + 'a> {
+        Box::new(Gitea { url, config, state })
+    }
+}
+
+impl Repo for Gitea<'_> {
+    fn name(&self) -> String {
+        "Gitea".to_string()
+    }
+
+    fn to_repo_url_with_path_and_branch(&self) -> Result<String, GrowseError> {
+        self.to_repo_url_with_path()
+    }
+
+    fn to_repo_url_with_path(&self) -> Result<String, GrowseError> {
+        let url = self.to_repo_url_with_branch()?;
+        let path = encode(self.state.path()?);
+
+        Ok(format!("{url}/{path}"))
+    }
+
+    fn to_repo_url_with_path_and_branch_and_line_number(&self) -> Result<String, GrowseError> {
+        self.to_repo_url_with_path_and_line_number()
+    }
+
+    fn to_repo_url_with_path_and_line_number(&self) -> Result<String, GrowseError> {
+        let url = self.to_repo_url_with_path()?;
+        let fragment = self.state.line_fragment("L")?;
+
+        Ok(format!("{url}#{fragment}"))
+    }
+
+    fn to_repo_url_with_branch(&self) -> Result<String, GrowseError> {
+        let repo_url = self.to_repo_url()?;
+        let ref_path = self.ref_path();
+
+        Ok(format!("{repo_url}/src/{ref_path}"))
+    }
+
+    fn to_pull_request_url(&self) -> Result<String, GrowseError> {
+        let repo_url = self.to_repo_url()?;
+        let branch = encode(&self.state.branch);
+
+        // without a base the compare page uses the default branch
+        Ok(format!("{repo_url}/compare/{branch}"))
+    }
+
+    fn to_commit_url(&self) -> Result<String, GrowseError> {
+        let repo_url = self.to_repo_url()?;
+        let commit = self.state.commit()?;
+
+        Ok(format!("{repo_url}/commit/{commit}"))
+    }
+
+    fn to_compare_url(&self) -> Result<String, GrowseError> {
+        let repo_url = self.to_repo_url()?;
+        let (base, head) = self.state.compare()?;
+        let base = encode(short_ref(base));
+        let head = encode(short_ref(head));
+
+        Ok(format!("{repo_url}/compare/{base}...{head}"))
+    }
+
+    fn to_blame_url(&self) -> Result<String, GrowseError> {
+        let repo_url = self.to_repo_url()?;
+        let ref_path = self.ref_path();
+        let path = encode(self.state.path()?);
+        let url = format!("{repo_url}/blame/{ref_path}/{path}");
+
+        if self.state.line_number.is_some() {
+            let fragment = self.state.line_fragment("L")?;
+            Ok(format!("{url}#{fragment}"))
+        } else {
+            Ok(url)
+        }
+    }
+
+    fn to_history_url(&self) -> Result<String, GrowseError> {
+        let repo_url = self.to_repo_url()?;
+        let ref_path = self.ref_path();
+        let url = format!("{repo_url}/commits/{ref_path}");
+
+        match &self.state.path {
+            Some(path) => Ok(format!("{url}/{}", encode(path))),
+            None => Ok(url),
+        }
+    }
+
+    fn to_raw_url(&self) -> Result<String, GrowseError> {
+        let repo_url = self.to_repo_url()?;
+        let ref_path = self.ref_path();
+        let path = encode(self.state.path()?);
+
+        Ok(format!("{repo_url}/raw/{ref_path}/{path}"))
+    }
+
+    fn to_repo_url(&self) -> Result<String, GrowseError> {
+        let base_url = self.config.base_url(self.url)?;
+        let fullname = &self.url.fullname;
+
+        Ok(format!("{base_url}/{fullname}"))
+    }
+}
+
+impl Gitea<'_> {
+    // Gitea qualifies the ref with its type, e.g. branch/main or commit/<sha>
+    fn ref_path(&self) -> String {
+        match &self.state.commit {
+            Some(commit) => format!("commit/{commit}"),
+            None => format!("branch/{}", encode(&self.state.branch)),
+        }
+    }
+}
@@ -1,51 +1,144 @@
 use crate::*;
 
-impl Repo for GitHub {
-    fn is_host(&self) -> bool {
-        self.url.host.as_ref().unwrap().contains("github")
+pub struct GitHub<'a> {
+    url: &'a GitUrl,
+    config: &'a GrowseConfig,
+    state: &'a GrowseState,
+}
+
+pub struct GitHubProvider;
+
+impl Provider for GitHubProvider {
+    fn backends(&self) -> &[&str] {
+        &["github"]
     }
 
-    fn to_repo_url_with_path_and_branch(&self) -> Result<String, Box<dyn std::error::Error>> {
-        return self.to_repo_url_with_path();
+    fn confidence(&self, url: &GitUrl, _config: &GrowseConfig) -> Confidence {
+        match url.host.as_deref() {
+            None => Confidence::No,
+            Some("github.com") => Confidence::Known,
+            Some(host) => Confidence::mentioned(host, "github"),
+        }
     }
 
-    fn to_repo_url_with_path_and_branch_and_line_number(
+    fn repo<'a>(
         &self,
-    ) -> Result<String, Box<dyn std::error::Error>> {
-        let url = self.to_repo_url_with_path_and_branch().unwrap();
-        let line_number = self.state.line_number.unwrap();
+        url: &'a GitUrl,
+        config: &'a GrowseConfig,
+        state: &'a GrowseState,
+    ) -> Box<dyn Repo + 'a> {
+        Box::new(GitHub { url, config, state })
+    }
+}
+
+impl Repo for GitHub<'_> {
+    fn name(&self) -> String {
+        "GitHub".to_string()
+    }
 
-        Ok(format!("{url}#L{line_number}"))
+    fn to_repo_url_with_path_and_branch(&self) -> Result<String, GrowseError> {
+        self.to_repo_url_with_path()
     }
 
-    fn to_repo_url_with_path_and_line_number(&self) -> Result<String, Box<dyn std::error::Error>> {
-        let url = self.to_repo_url_with_path().unwrap();
-        let line_number = self.state.line_number.unwrap();
+    fn to_repo_url_with_path_and_branch_and_line_number(&self) -> Result<String, GrowseError> {
+        let url = self.to_repo_url_with_path_and_branch()?;
+        let fragment = self.state.line_fragment("L")?;
 
-        Ok(format!("{url}#L{line_number}"))
+        Ok(format!("{url}#{fragment}"))
     }
 
-    fn to_repo_url_with_path(&self) -> Result<String, Box<dyn std::error::Error>> {
-        let branch = &self.state.branch;
+    fn to_repo_url_with_path_and_line_number(&self) -> Result<String, GrowseError> {
+        let url = self.to_repo_url_with_path()?;
+        let fragment = self.state.line_fragment("L")?;
+
+        Ok(format!("{url}#{fragment}"))
+    }
+
+    fn to_repo_url_with_path(&self) -> Result<String, GrowseError> {
+        let git_ref = encode(self.state.git_ref());
         let fullname = &self.url.fullname;
-        let host = self.url.host.clone().ok_or("No host found")?;
-        let path = self.state.path.clone().ok_or("No path found")?;
+        let base_url = self.config.base_url(self.url)?;
+        let path = encode(self.state.path()?);
+
+        Ok(format!("{base_url}/{fullname}/blob/{git_ref}/{path}"))
+    }
+
+    fn to_repo_url_with_branch(&self) -> Result<String, GrowseError> {
+        let base_url = self.config.base_url(self.url)?;
+        let git_ref = encode(self.state.git_ref());
+        let fullname = &self.url.fullname;
+
+        Ok(format!("{base_url}/{fullname}/tree/{git_ref}"))
+    }
+
+    fn to_pull_request_url(&self) -> Result<String, GrowseError> {
+        let repo_url = self.to_repo_url()?;
+        let branch = encode(&self.state.branch);
+
+        Ok(format!("{repo_url}/pull/new/{branch}"))
+    }
+
+    fn to_commit_url(&self) -> Result<String, GrowseError> {
+        let repo_url = self.to_repo_url()?;
+        let commit = self.state.commit()?;
+
+        Ok(format!("{repo_url}/commit/{commit}"))
+    }
+
+    fn to_compare_url(&self) -> Result<String, GrowseError> {
+        let repo_url = self.to_repo_url()?;
+        let (base, head) = self.state.compare()?;
+        let base = encode(short_ref(base));
+        let head = encode(short_ref(head));
+
+        Ok(format!("{repo_url}/compare/{base}...{head}"))
+    }
+
+    fn to_blame_url(&self) -> Result<String, GrowseError> {
+        let repo_url = self.to_repo_url()?;
+        let git_ref = encode(self.state.git_ref());
+        let path = encode(self.state.path()?);
+        let url = format!("{repo_url}/blame/{git_ref}/{path}");
+
+        if self.state.line_number.is_some() {
+            let fragment = self.state.line_fragment("L")?;
+            Ok(format!("{url}#{fragment}"))
+        } else {
+            Ok(url)
+        }
+    }
+
+    fn to_history_url(&self) -> Result<String, GrowseError> {
+        let repo_url = self.to_repo_url()?;
+        let git_ref = encode(self.state.git_ref());
+        let url = format!("{repo_url}/commits/{git_ref}");
 
-        Ok(format!("https://{host}/{fullname}/blob/{branch}/{path}"))
+        match &self.state.path {
+            Some(path) => Ok(format!("{url}/{}", encode(path))),
+            None => Ok(url),
+        }
     }
 
-    fn to_repo_url_with_branch(&self) -> Result<String, Box<dyn std::error::Error>> {
-        let host = self.url.host.clone().ok_or("No host found")?;
-        let branch = &self.state.branch;
+    fn to_raw_url(&self) -> Result<String, GrowseError> {
+        let base_url = self.config.base_url(self.url)?;
         let fullname = &self.url.fullname;
+        let git_ref = encode(self.state.git_ref());
+        let path = encode(self.state.path()?);
 
-        Ok(format!("https://{host}/{fullname}/tree/{branch}"))
+        // github.com serves raw content from a separate domain, GitHub Enterprise does not
+        if base_url == "https://github.com" {
+            Ok(format!(
+                "https://raw.githubusercontent.com/{fullname}/{git_ref}/{path}"
+            ))
+        } else {
+            Ok(format!("{base_url}/{fullname}/raw/{git_ref}/{path}"))
+        }
     }
 
-    fn to_repo_url(&self) -> Result<String, Box<dyn std::error::Error>> {
-        let host = self.url.host.clone().ok_or("No host found")?;
+    fn to_repo_url(&self) -> Result<String, GrowseError> {
+        let base_url = self.config.base_url(self.url)?;
         let fullname = &self.url.fullname;
 
-        Ok(format!("https://{host}/{fullname}"))
+        Ok(format!("{base_url}/{fullname}"))
     }
 }
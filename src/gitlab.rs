@@ -1,46 +1,135 @@
 use crate::*;
 
-impl Repo for GitLab {
-    fn is_host(&self) -> bool {
-        self.url.host.as_ref().unwrap().contains("gitlab")
-    }
+pub struct GitLab<'a> {
+    url: &'a GitUrl,
+    config: &'a GrowseConfig,
+    state: &'a GrowseState,
+}
+
+pub struct GitLabProvider;
 
-    fn to_repo_url_with_path_and_branch(&self) -> Result<String, Box<dyn std::error::Error>> {
-        Err("Not implemented".into())
+impl Provider for GitLabProvider {
+    fn backends(&self) -> &[&str] {
+        &["gitlab"]
     }
 
-    fn to_repo_url_with_path(&self) -> Result<String, Box<dyn std::error::Error>> {
-        Err("Not implemented".into())
+    fn confidence(&self, url: &GitUrl, _config: &GrowseConfig) -> Confidence {
+        match url.host.as_deref() {
+            None => Confidence::No,
+            Some("gitlab.com") => Confidence::Known,
+            Some(host) => Confidence::mentioned(host, "gitlab"),
+        }
     }
 
-    fn to_repo_url_with_path_and_branch_and_line_number(
+    fn repo<'a>(
         &self,
-    ) -> Result<String, Box<dyn std::error::Error>> {
-        Err("Not implemented".into())
+        url: &'a GitUrl,
+        config: &'a GrowseConfig,
+        state: &'a GrowseState,
+    ) -> Box<dyn Repo + 'a> {
+        Box::new(GitLab { url, config, state })
+    }
+}
+
+impl Repo for GitLab<'_> {
+    fn name(&self) -> String {
+        "GitLab".to_string()
     }
 
-    fn to_repo_url_with_path_and_line_number(&self) -> Result<String, Box<dyn std::error::Error>> {
-        Err("Not implemented".into())
+    fn to_repo_url_with_path_and_branch(&self) -> Result<String, GrowseError> {
+        self.to_repo_url_with_path()
+    }
+
+    fn to_repo_url_with_path(&self) -> Result<String, GrowseError> {
+        let repo_url = self.to_repo_url()?;
+        let git_ref = encode(self.state.git_ref());
+        let path = encode(self.state.path()?);
+
+        Ok(format!("{repo_url}/-/blob/{git_ref}/{path}"))
+    }
+
+    fn to_repo_url_with_path_and_branch_and_line_number(&self) -> Result<String, GrowseError> {
+        let url = self.to_repo_url_with_path_and_branch()?;
+        let fragment = self.state.line_fragment("")?;
+
+        Ok(format!("{url}#{fragment}"))
+    }
+
+    fn to_repo_url_with_path_and_line_number(&self) -> Result<String, GrowseError> {
+        let url = self.to_repo_url_with_path()?;
+        let fragment = self.state.line_fragment("")?;
+
+        Ok(format!("{url}#{fragment}"))
+    }
+
+    fn to_repo_url_with_branch(&self) -> Result<String, GrowseError> {
+        let repo_url = self.to_repo_url()?;
+        let git_ref = encode(self.state.git_ref());
+
+        Ok(format!("{repo_url}/-/tree/{git_ref}"))
     }
 
-    fn to_repo_url_with_branch(&self) -> Result<String, Box<dyn std::error::Error>> {
-        let host = self.url.host.clone().ok_or("No host found")?;
-        let owner = self.url.owner.clone().ok_or("No owner found")?;
-        let branch_name = format!("refs/heads/{}", self.state.branch);
+    fn to_pull_request_url(&self) -> Result<String, GrowseError> {
+        let repo_url = self.to_repo_url()?;
         let new_url = Url::parse_with_params(
-            &format!(
-                "https://{}/{}/{}/-/tree/{}",
-                host, owner, self.url.name, branch_name
-            ),
-            &[("at", branch_name)],
+            &format!("{repo_url}/-/merge_requests/new"),
+            &[("merge_request[source_branch]", self.state.branch.as_str())],
         )?;
-        Ok(new_url.as_str().to_string())
+
+        Ok(new_url.to_string())
+    }
+
+    fn to_commit_url(&self) -> Result<String, GrowseError> {
+        let repo_url = self.to_repo_url()?;
+        let commit = self.state.commit()?;
+
+        Ok(format!("{repo_url}/-/commit/{commit}"))
+    }
+
+    fn to_compare_url(&self) -> Result<String, GrowseError> {
+        let repo_url = self.to_repo_url()?;
+        let (base, head) = self.state.compare()?;
+        let base = encode(short_ref(base));
+        let head = encode(short_ref(head));
+
+        Ok(format!("{repo_url}/-/compare/{base}...{head}"))
+    }
+
+    fn to_blame_url(&self) -> Result<String, GrowseError> {
+        let repo_url = self.to_repo_url()?;
+        let git_ref = encode(self.state.git_ref());
+        let path = encode(self.state.path()?);
+        let url = format!("{repo_url}/-/blame/{git_ref}/{path}");
+
+        if self.state.line_number.is_some() {
+            let fragment = self.state.line_fragment("")?;
+            Ok(format!("{url}#{fragment}"))
+        } else {
+            Ok(url)
+        }
     }
 
-    fn to_repo_url(&self) -> Result<String, Box<dyn std::error::Error>> {
-        if self.config.verbose {
-            println!("gitlab_url_to_repo_url: {:?}", self.url);
+    fn to_history_url(&self) -> Result<String, GrowseError> {
+        let repo_url = self.to_repo_url()?;
+        let git_ref = encode(self.state.git_ref());
+        let url = format!("{repo_url}/-/commits/{git_ref}");
+
+        match &self.state.path {
+            Some(path) => Ok(format!("{url}/{}", encode(path))),
+            None => Ok(url),
         }
+    }
+
+    fn to_raw_url(&self) -> Result<String, GrowseError> {
+        let repo_url = self.to_repo_url()?;
+        let git_ref = encode(self.state.git_ref());
+        let path = encode(self.state.path()?);
+
+        Ok(format!("{repo_url}/-/raw/{git_ref}/{path}"))
+    }
+
+    fn to_repo_url(&self) -> Result<String, GrowseError> {
+        log::debug!("gitlab_url_to_repo_url: {:?}", self.url);
         // git@gitlab.com:gitlab-com/gl-infra/gitlab-dedicated/library/terraform/cloudwatch_log_export.git
         // https://gitlab.com/gitlab-com/gl-infra/gitlab-dedicated/library/terraform/cloudwatch_log_export.git
         // default head
@@ -48,22 +137,13 @@ impl Repo for GitLab {
         // branch
         // https://gitlab.com/gitlab-com/gl-infra/gitlab-dedicated/library/terraform/cloudwatch_log_export/-/tree/1.1.0?ref_type=tags
 
-        let host = self.url.host.clone().ok_or("No host found")?;
-        let owner = self.url.owner.clone().ok_or("No owner found")?;
-        let parts = self
-            .url
-            .path
-            .trim_matches('/')
-            .split('/')
-            .collect::<Vec<&str>>();
-
-        // FIXME this doesn't really work
-        let organization = parts[0];
-        // use path??
-        let new_url = format!(
-            "https://{}/{}/{}/{}",
-            host, organization, owner, self.url.name
-        );
+        let base_url = self.config.base_url(self.url)?;
+        // GitLab supports arbitrarily nested subgroups, so the full namespace
+        // is taken from the remote path rather than from owner/name.
+        let path = self.url.path.trim_matches('/');
+        let path = path.strip_suffix(".git").unwrap_or(path);
+
+        let new_url = format!("{base_url}/{path}");
         Ok(new_url)
     }
 }
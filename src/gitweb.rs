@@ -0,0 +1,121 @@
+use crate::*;
+
+pub struct Gitweb<'a> {
+    url: &'a GitUrl,
+    config: &'a GrowseConfig,
+    state: &'a GrowseState,
+}
+
+pub struct GitwebProvider;
+
+impl Provider for GitwebProvider {
+    fn backends(&self) -> &[&str] {
+        &["gitweb"]
+    }
+
+    fn confidence(&self, _url: &GitUrl, _config: &GrowseConfig) -> Confidence {
+        Confidence::No
+    }
+
+    fn repo<'a>(
+        &self,
+        url: &'a GitUrl,
+        config: &'a GrowseConfig,
+        state: &'a GrowseState,
+    ) -> Box<dyn Repo + 'a> {
+        Box::new(Gitweb { url, config, state })
+    }
+}
+
+impl Repo for Gitweb<'_> {
+    fn name(&self) -> String {
+        "gitweb".to_string()
+    }
+
+    fn to_repo_url_with_path_and_branch(&self) -> Result<String, GrowseError> {
+        self.to_repo_url_with_path()
+    }
+
+    fn to_repo_url_with_path(&self) -> Result<String, GrowseError> {
+        let path = self.state.path()?;
+        let git_ref = self.state.git_ref();
+
+        self.action_url(&[("a", "blob"), ("f", path), ("hb", git_ref)])
+    }
+
+    fn to_repo_url_with_path_and_branch_and_line_number(&self) -> Result<String, GrowseError> {
+        self.to_repo_url_with_path_and_line_number()
+    }
+
+    fn to_repo_url_with_path_and_line_number(&self) -> Result<String, GrowseError> {
+        let url = self.to_repo_url_with_path()?;
+        let line_number = self.state.line_number()?;
+
+        Ok(format!("{url}#l{line_number}"))
+    }
+
+    fn to_repo_url_with_branch(&self) -> Result<String, GrowseError> {
+        let git_ref = self.state.git_ref();
+
+        self.action_url(&[("a", "tree"), ("hb", git_ref)])
+    }
+
+    fn to_commit_url(&self) -> Result<String, GrowseError> {
+        let commit = self.state.commit()?;
+
+        self.action_url(&[("a", "commit"), ("h", commit)])
+    }
+
+    fn to_compare_url(&self) -> Result<String, GrowseError> {
+        let (base, head) = self.state.compare()?;
+
+        self.action_url(&[("a", "commitdiff"), ("h", head), ("hp", base)])
+    }
+
+    fn to_blame_url(&self) -> Result<String, GrowseError> {
+        let path = self.state.path()?;
+        let git_ref = self.state.git_ref();
+        let url = self.action_url(&[("a", "blame"), ("f", path), ("hb", git_ref)])?;
+
+        match self.state.line_number {
+            Some(line_number) => Ok(format!("{url}#l{line_number}")),
+            None => Ok(url),
+        }
+    }
+
+    fn to_history_url(&self) -> Result<String, GrowseError> {
+        let git_ref = self.state.git_ref();
+
+        match &self.state.path {
+            Some(path) => self.action_url(&[("a", "history"), ("f", path), ("hb", git_ref)]),
+            None => self.action_url(&[("a", "shortlog"), ("h", git_ref)]),
+        }
+    }
+
+    fn to_raw_url(&self) -> Result<String, GrowseError> {
+        let path = self.state.path()?;
+        let git_ref = self.state.git_ref();
+
+        self.action_url(&[("a", "blob_plain"), ("f", path), ("hb", git_ref)])
+    }
+
+    fn to_repo_url(&self) -> Result<String, GrowseError> {
+        let base_url = self.config.base_url(self.url)?;
+        let project = encode(self.url.path.trim_matches('/'));
+
+        Ok(format!("{base_url}/?p={project}"))
+    }
+}
+
+impl Gitweb<'_> {
+    // gitweb separates its query parameters with semicolons, the values are
+    // percent-encoded so a ; or # in a path doesn't cut the link short
+    fn action_url(&self, params: &[(&str, &str)]) -> Result<String, GrowseError> {
+        let mut url = self.to_repo_url()?;
+        for (name, value) in params {
+            url.push_str(&format!(";{name}={}", encode(value)));
+        }
+
+        Ok(url)
+    }
+}
@@ -0,0 +1,2201 @@
+//! Links to the web interface of a git hosting service for a repository, a
+//! file, a line range, a commit and more.
+//!
+//! ```no_run
+//! use growse::{link_for, LinkOptions};
+//! use std::path::Path;
+//!
+//! let url = link_for(Path::new("."), Some("src/main.rs"), Some((10, Some(20))), &LinkOptions::default())?;
+//! println!("{url}");
+//! # Ok::<(), growse::GrowseError>(())
+//! ```
+//!
+//! [`remote_url_to_repo_url`] builds the same links from a remote url and a
+//! [`GrowseState`] without touching a repository.
+
+mod azure;
+mod bitbucket;
+mod bitbucket_cloud;
+mod cgit;
+mod codecommit;
+mod error;
+mod generic;
+mod gerrit;
+mod gitea;
+mod github;
+mod gitlab;
+mod gitweb;
+mod registry;
+mod sourcehut;
+mod ssh_config;
+mod template;
+
+pub use error::GrowseError;
+pub use git_url_parse::GitUrl;
+pub use registry::{Confidence, Provider, Registry};
+pub use ssh_config::SshConfig;
+pub use template::UrlTemplates;
+pub use url::Url;
+
+use git2::{ErrorCode, Remote, Repository};
+use git_url_parse::Scheme;
+use regex::Regex;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::Path;
+
+#[derive(Debug, Deserialize, Clone)]
+struct GrowseConfigFile {
+    #[serde(default)]
+    growse: GrowseConfig,
+    #[serde(default)]
+    hosts: HashMap<String, HostConfig>,
+}
+
+/// Per host settings, keyed by the host name of the remote.
+#[derive(Debug, Deserialize, Clone, Default)]
+#[non_exhaustive]
+pub struct HostConfig {
+    /// Key of the provider building the links, see [`Provider::backends`].
+    /// Skips detection when set.
+    pub backend: Option<String>,
+    /// Web interface location when it differs from the remote, e.g. an ssh only host.
+    pub hostname: Option<String>,
+    /// Scheme of the web interface, `https` by default.
+    pub scheme: Option<String>,
+    /// Port of the web interface when it isn't the scheme's default.
+    pub port: Option<u16>,
+    /// Links built from user defined templates instead of a backend.
+    pub templates: Option<UrlTemplates>,
+}
+
+/// Settings from the `[growse]` and `[hosts]` tables of `growse.toml`.
+#[derive(Debug, Deserialize, Clone, Default)]
+#[non_exhaustive]
+pub struct GrowseConfig {
+    /// Link to the branch rather than the repository's default view.
+    #[serde(default)]
+    pub use_branch: bool,
+    /// Link to the checked out branch.
+    #[serde(default)]
+    pub current_branch: bool,
+    /// Open `https://<host>/<owner>/<repo>` for hosts no backend recognises.
+    #[serde(default)]
+    pub generic_fallback: bool,
+    /// Settings of the `[hosts]` table, keyed by host name.
+    #[serde(skip)]
+    pub hosts: HashMap<String, HostConfig>,
+    /// Host aliases of ssh remotes, empty unless loaded with [`SshConfig::load`].
+    #[serde(skip)]
+    pub ssh_config: SshConfig,
+}
+
+impl GrowseConfig {
+    /// Read a `growse.toml` config file.
+    pub fn from_file(path: &Path) -> Result<GrowseConfig, GrowseError> {
+        let contents = std::fs::read_to_string(path).map_err(|source| GrowseError::ConfigRead {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        let config_file: GrowseConfigFile =
+            toml::from_str(&contents).map_err(|source| GrowseError::Config {
+                path: path.to_path_buf(),
+                source,
+            })?;
+        Ok(GrowseConfig {
+            hosts: config_file.hosts,
+            ..config_file.growse
+        })
+    }
+
+    // Backend explicitly configured for the host, takes precedence over detection
+    fn backend_for(&self, host: Option<&str>) -> Option<&str> {
+        self.hosts.get(host?)?.backend.as_deref()
+    }
+
+    fn base_url(&self, url: &GitUrl) -> Result<String, GrowseError> {
+        let host = url
+            .host
+            .as_deref()
+            .ok_or(GrowseError::MissingRemotePart("host"))?;
+        Ok(self.web_base_url(host, host))
+    }
+
+    // scheme://hostname[:port] of the web interface for a remote host,
+    // https on default_hostname unless overridden in the host config
+    fn web_base_url(&self, host: &str, default_hostname: &str) -> String {
+        let host_config = self.hosts.get(host);
+        let scheme = host_config
+            .and_then(|host_config| host_config.scheme.as_deref())
+            .unwrap_or("https");
+        let hostname = host_config
+            .and_then(|host_config| host_config.hostname.as_deref())
+            .unwrap_or(default_hostname);
+        match host_config.and_then(|host_config| host_config.port) {
+            Some(port) => format!("{scheme}://{hostname}:{port}"),
+            None => format!("{scheme}://{hostname}"),
+        }
+    }
+}
+
+/// Page of the web interface to link to.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[non_exhaustive]
+pub enum LinkKind {
+    /// The repository, a branch, a file or lines of a file.
+    #[default]
+    Browse,
+    /// The pull request, or Gerrit change, of the branch.
+    PullRequest,
+    /// The commit page of [`GrowseState::commit`].
+    Commit,
+    /// The changes between the refs of [`GrowseState::compare`].
+    Compare,
+    /// The blame view of a file, optionally at its lines.
+    Blame,
+    /// The commit history of a file or of the branch.
+    History,
+    /// The raw content of a file.
+    Raw,
+}
+
+/// Everything a link is built from besides the remote url.
+#[derive(Clone, Debug, Default)]
+#[non_exhaustive]
+pub struct GrowseState {
+    /// File path from the root of the work tree.
+    pub path: Option<String>,
+    /// Line of the file to highlight.
+    pub line_number: Option<u32>,
+    /// Last line of a highlighted range.
+    pub end_line_number: Option<u32>,
+    /// Branch to link to, the default branch unless one was asked for.
+    pub branch: String,
+    /// Commit SHA the link is pinned to.
+    pub commit: Option<String>,
+    /// (base, head) as fully qualified refs or commit SHAs.
+    pub compare: Option<(String, String)>,
+    /// Gerrit Change-Id trailer of the HEAD commit.
+    pub change_id: Option<String>,
+    /// Page to link to.
+    pub link_kind: LinkKind,
+}
+
+impl GrowseState {
+    /// A pinned commit takes precedence over the branch.
+    pub fn git_ref(&self) -> &str {
+        self.commit.as_deref().unwrap_or(&self.branch)
+    }
+
+    /// The file path, for links that need one.
+    pub fn path(&self) -> Result<&str, GrowseError> {
+        self.path
+            .as_deref()
+            .ok_or(GrowseError::MissingState("path"))
+    }
+
+    /// The line number, for links that need one.
+    pub fn line_number(&self) -> Result<u32, GrowseError> {
+        self.line_number
+            .ok_or(GrowseError::MissingState("line number"))
+    }
+
+    /// The pinned commit, for links that need one.
+    pub fn commit(&self) -> Result<&str, GrowseError> {
+        self.commit
+            .as_deref()
+            .ok_or(GrowseError::MissingState("commit"))
+    }
+
+    /// The (base, head) refs, for compare links.
+    pub fn compare(&self) -> Result<(&str, &str), GrowseError> {
+        self.compare
+            .as_ref()
+            .map(|(base, head)| (base.as_str(), head.as_str()))
+            .ok_or(GrowseError::MissingState("refs to compare"))
+    }
+
+    // L10, or a range as L10-L25 with end_prefix "L" or L10-25 with ""
+    fn line_fragment(&self, end_prefix: &str) -> Result<String, GrowseError> {
+        let line_number = self.line_number()?;
+        match self.end_line_number {
+            Some(end_line_number) => Ok(format!("L{line_number}-{end_prefix}{end_line_number}")),
+            None => Ok(format!("L{line_number}")),
+        }
+    }
+}
+
+/// Links for one hosting service, implemented by each backend. Link kinds a
+/// service has no page for can be left out, they are unsupported by default.
+pub trait Repo {
+    /// Name of the hosting service in error messages, e.g. `GitHub`.
+    fn name(&self) -> String;
+    /// The repository's default view.
+    fn to_repo_url(&self) -> Result<String, GrowseError>;
+    /// The tree of the branch or pinned commit.
+    fn to_repo_url_with_branch(&self) -> Result<String, GrowseError>;
+    /// A file on the default branch.
+    fn to_repo_url_with_path(&self) -> Result<String, GrowseError>;
+    /// A file on the branch or pinned commit.
+    fn to_repo_url_with_path_and_branch(&self) -> Result<String, GrowseError>;
+    /// Lines of a file on the default branch.
+    fn to_repo_url_with_path_and_line_number(&self) -> Result<String, GrowseError>;
+    /// Lines of a file on the branch or pinned commit.
+    fn to_repo_url_with_path_and_branch_and_line_number(&self) -> Result<String, GrowseError>;
+    /// The pull request of the branch, or a new one when the host can't look it up.
+    fn to_pull_request_url(&self) -> Result<String, GrowseError> {
+        Err(GrowseError::unsupported(&self.name(), "pull request"))
+    }
+    /// The commit page, see [`LinkKind::Commit`].
+    fn to_commit_url(&self) -> Result<String, GrowseError> {
+        Err(GrowseError::unsupported(&self.name(), "commit"))
+    }
+    /// The compare view, see [`LinkKind::Compare`].
+    fn to_compare_url(&self) -> Result<String, GrowseError> {
+        Err(GrowseError::unsupported(&self.name(), "compare"))
+    }
+    /// The blame view, see [`LinkKind::Blame`].
+    fn to_blame_url(&self) -> Result<String, GrowseError> {
+        Err(GrowseError::unsupported(&self.name(), "blame"))
+    }
+    /// The commit history, see [`LinkKind::History`].
+    fn to_history_url(&self) -> Result<String, GrowseError> {
+        Err(GrowseError::unsupported(&self.name(), "history"))
+    }
+    /// The raw content of a file, see [`LinkKind::Raw`].
+    fn to_raw_url(&self) -> Result<String, GrowseError> {
+        Err(GrowseError::unsupported(&self.name(), "raw"))
+    }
+}
+
+/// Link for a remote url, picking the backend from the host config or the host name.
+pub fn remote_url_to_repo_url(
+    remote_url: &str,
+    state: &GrowseState,
+    config: &GrowseConfig,
+) -> Result<String, GrowseError> {
+    Registry::default().link(remote_url, state, config)
+}
+
+/// What to link to besides the file and lines passed to [`link_for`].
+#[derive(Clone, Debug, Default)]
+#[non_exhaustive]
+pub struct LinkOptions {
+    /// Remote to link to, the first remote of the repository by default.
+    pub remote: Option<String>,
+    /// Branch to link to instead of the remote's default branch.
+    pub branch: Option<String>,
+    /// Revision to pin the link to, e.g. `HEAD` for a permalink.
+    pub commit: Option<String>,
+    /// Base of a [`LinkKind::Compare`] link, the default branch when unset.
+    pub compare_base: Option<String>,
+    /// Head of a [`LinkKind::Compare`] link, the current branch when unset.
+    pub compare_head: Option<String>,
+    /// Page to link to.
+    pub link_kind: LinkKind,
+    /// Settings, usually read with [`GrowseConfig::from_file`].
+    pub config: GrowseConfig,
+}
+
+/// Link to the web interface for the repository at `repo_path`, optionally to
+/// `file`, a path from the root of the work tree, and its `lines` as the first
+/// and optional last line. Uses the built-in backends, see [`Registry::link_for`].
+pub fn link_for(
+    repo_path: &Path,
+    file: Option<&str>,
+    lines: Option<(u32, Option<u32>)>,
+    options: &LinkOptions,
+) -> Result<Url, GrowseError> {
+    Registry::default().link_for(repo_path, file, lines, options)
+}
+
+/// Path, line number and end line number.
+pub type PathArg = (String, Option<u32>, Option<u32>);
+
+/// Split a path argument into the path and an optional line or line range.
+/// Accepts `path:10`, `path:10-25`, `path#L10` and `path#L10-L25`.
+pub fn parse_path(path: &str) -> Result<PathArg, GrowseError> {
+    let re = Regex::new(r"^(.*?)(?::(\d+)(?:-(\d+))?|#L(\d+)(?:-L?(\d+))?)?$").unwrap();
+    let invalid = |reason: String| GrowseError::InvalidPath {
+        path: path.to_string(),
+        reason,
+    };
+    let captures = re
+        .captures(path)
+        .ok_or_else(|| invalid("expected path, path:N or path:N-M".to_string()))?;
+    let file_path = captures.get(1).map_or("", |m| m.as_str()).to_string();
+    let line_number = captures
+        .get(2)
+        .or(captures.get(4))
+        .map(|m| m.as_str().parse::<u32>())
+        .transpose()
+        .map_err(|e| invalid(e.to_string()))?;
+    let end_line_number = captures
+        .get(3)
+        .or(captures.get(5))
+        .map(|m| m.as_str().parse::<u32>())
+        .transpose()
+        .map_err(|e| invalid(e.to_string()))?;
+
+    match (line_number, end_line_number) {
+        (Some(start), Some(end)) if end < start => {
+            Err(invalid(format!("line range {start}-{end} is reversed")))
+        }
+        (Some(start), Some(end)) if end == start => Ok((file_path, line_number, None)),
+        _ => Ok((file_path, line_number, end_line_number)),
+    }
+}
+
+fn resolve_commit(repo: &Repository, rev: &str) -> Result<String, GrowseError> {
+    let commit = repo
+        .revparse_single(rev)
+        .and_then(|object| object.peel_to_commit())
+        .map_err(|source| GrowseError::InvalidRevision {
+            rev: rev.to_string(),
+            source,
+        })?;
+    Ok(commit.id().to_string())
+}
+
+// Branches and tags keep their name so the compare view stays readable,
+// anything else is pinned to the commit SHA.
+fn resolve_compare_ref(repo: &Repository, rev: &str) -> Result<String, GrowseError> {
+    if let Ok(reference) = repo.resolve_reference_from_short_name(rev) {
+        let name = reference
+            .name()
+            .ok_or(GrowseError::InvalidUtf8("Reference name"))?;
+        if reference.is_branch() || reference.is_tag() {
+            return Ok(name.to_string());
+        }
+        if reference.is_remote() {
+            // refs/remotes/<remote>/<branch> is refs/heads/<branch> on the host
+            if let Some((_, branch)) = name.trim_start_matches("refs/remotes/").split_once('/') {
+                return Ok(format!("refs/heads/{branch}"));
+            }
+        }
+    }
+    resolve_commit(repo, rev)
+}
+
+// Percent-encode everything but unreserved characters, '/' is kept so paths
+// and refs stay readable.
+fn encode(value: &str) -> String {
+    let mut encoded = String::new();
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    encoded
+}
+
+// Strip the refs/heads/ or refs/tags/ prefix for hosts that take short ref names
+fn short_ref(git_ref: &str) -> &str {
+    git_ref
+        .strip_prefix("refs/heads/")
+        .or_else(|| git_ref.strip_prefix("refs/tags/"))
+        .unwrap_or(git_ref)
+}
+
+fn current_branch(repo: &Repository) -> Result<String, GrowseError> {
+    let head = repo.head()?;
+    if !head.is_branch() {
+        return Err(GrowseError::DetachedHead);
+    }
+    Ok(head
+        .shorthand()
+        .ok_or(GrowseError::InvalidUtf8("Branch name"))?
+        .to_string())
+}
+
+// Change-Id trailer Gerrit uses to track a change across patch sets
+fn head_change_id(repo: &Repository) -> Option<String> {
+    let commit = repo.head().ok()?.peel_to_commit().ok()?;
+    parse_change_id(commit.message()?)
+}
+
+fn parse_change_id(message: &str) -> Option<String> {
+    let re = Regex::new(r"(?m)^Change-Id:\s*(I[0-9a-f]{40})\s*$").unwrap();
+    re.captures_iter(message)
+        .last()
+        .map(|captures| captures[1].to_string())
+}
+
+fn default_remote(repo: &Repository) -> Result<String, GrowseError> {
+    let remote_names = repo.remotes()?;
+    let mut remotes = (&remote_names).into_iter().flatten();
+    if let Some(remote) = remotes.next() {
+        return Ok(remote.to_string());
+    }
+    Err(GrowseError::NoRemote(None))
+}
+
+fn default_branch(repo: &Repository, remote: &Remote) -> Result<String, GrowseError> {
+    let default_branch = remote.default_branch();
+    if let Ok(default_branch) = default_branch {
+        return Ok(default_branch
+            .as_str()
+            .ok_or(GrowseError::InvalidUtf8("Default branch name"))?
+            .to_string());
+    } else {
+        let remote_name = remote
+            .name()
+            .ok_or(GrowseError::InvalidUtf8("Remote name"))?;
+        let remote_ref = format!("refs/remotes/{}/HEAD", remote_name);
+        let reference = repo.resolve_reference_from_short_name(&remote_ref);
+        if let Ok(reference) = reference {
+            let remote_ref_prefix = format!("refs/remotes/{}/", remote_name);
+            let name = reference.name();
+            let short_name = name.and_then(|name| name.strip_prefix(&remote_ref_prefix));
+            log::debug!("reference name: {:?}", name);
+            log::debug!("resolved: {:?}", short_name);
+            if let Some(short_name) = short_name.filter(|short_name| *short_name != "HEAD") {
+                return Ok(short_name.to_string());
+            }
+        } else {
+            log::debug!("Could not resolve reference: {:?}", remote_ref);
+        }
+    }
+    // FIXME better strategy for finding a remote branch.
+    // look for local branches matching main or master??
+    Ok("master".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_config() -> GrowseConfig {
+        GrowseConfig {
+            use_branch: false,
+            current_branch: false,
+            generic_fallback: false,
+            hosts: HashMap::new(),
+            ssh_config: SshConfig::default(),
+        }
+    }
+
+    fn generate_test_state() -> GrowseState {
+        GrowseState {
+            branch: "master".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_simple_repo_link() {
+        let remote_urls = &[
+            "ssh://git@github.com/takac/git-open",
+            "https://github.com/takac/git-open",
+            "git@github.com:takac/git-open",
+        ];
+        let test_state = generate_test_state();
+        for url in remote_urls {
+            let expected = "https://github.com/takac/git-open";
+            assert_eq!(
+                expected,
+                remote_url_to_repo_url(url, &test_state, &test_config()).unwrap()
+            );
+            assert_eq!(
+                expected,
+                remote_url_to_repo_url(&format!("{}.git", url), &test_state, &test_config())
+                    .unwrap()
+            );
+        }
+    }
+
+    fn default_test(expected_to_input: HashMap<&str, &str>) {
+        default_test_with_state(&generate_test_state(), expected_to_input);
+    }
+
+    fn default_test_with_state(state: &GrowseState, expected_to_input: HashMap<&str, &str>) {
+        default_test_with_config(state, &test_config(), expected_to_input);
+    }
+
+    fn default_test_with_config(
+        state: &GrowseState,
+        config: &GrowseConfig,
+        expected_to_input: HashMap<&str, &str>,
+    ) {
+        for (expected, input) in expected_to_input {
+            assert_eq!(
+                expected,
+                remote_url_to_repo_url(input, state, config).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_bb_repo_link() {
+        default_test(HashMap::from([
+            (
+                "https://bitbucket.company.com/projects/takac/repos/git-open",
+                "ssh://git@bitbucket.company.com:7999/takac/git-open.git",
+            ),
+            (
+                "https://bitbucket.gi.de/projects/dig/repos/frontend",
+                "https://bitbucket.gi.de/scm/dig/frontend.git",
+            ),
+        ]));
+    }
+
+    #[test]
+    fn test_gitlab_repo_link() {
+        default_test(HashMap::from([(
+            "https://gitlab.com/takac/side-project/git-open",
+            "git@gitlab.com:takac/side-project/git-open.git",
+        )]));
+    }
+
+    #[test]
+    fn test_gitlab_nested_group_repo_link() {
+        let projects = &[
+            "takac/git-open",
+            "takac/side-project/git-open",
+            "gitlab-com/gl-infra/gitlab-dedicated/library/terraform/cloudwatch_log_export",
+        ];
+        for project in projects {
+            let expected = format!("https://gitlab.com/{project}");
+            let remote_urls = [
+                format!("git@gitlab.com:{project}.git"),
+                format!("https://gitlab.com/{project}.git"),
+                format!("ssh://git@gitlab.com/{project}.git"),
+                format!("ssh://git@gitlab.com/{project}"),
+            ];
+            for url in remote_urls {
+                assert_eq!(
+                    expected,
+                    remote_url_to_repo_url(&url, &generate_test_state(), &test_config()).unwrap()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_gitlab_repo_link_with_branch() {
+        let state = GrowseState {
+            branch: "feature/x".to_string(),
+            ..generate_test_state()
+        };
+        let config = GrowseConfig {
+            use_branch: true,
+            ..test_config()
+        };
+
+        let expected = "https://gitlab.com/takac/side-project/git-open/-/tree/feature/x";
+        let url = "git@gitlab.com:takac/side-project/git-open.git";
+        assert_eq!(
+            expected,
+            remote_url_to_repo_url(url, &state, &config).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_gitlab_repo_link_with_path_line_nos() {
+        let state = GrowseState {
+            branch: "main".to_string(),
+            path: Some("src/main.rs".to_string()),
+            line_number: Some(10),
+            ..generate_test_state()
+        };
+        let url = "git@gitlab.com:takac/side-project/git-open.git";
+
+        assert_eq!(
+            "https://gitlab.com/takac/side-project/git-open/-/blob/main/src/main.rs#L10",
+            remote_url_to_repo_url(url, &state, &test_config()).unwrap()
+        );
+
+        let state = GrowseState {
+            line_number: None,
+            ..state
+        };
+        let config = GrowseConfig {
+            use_branch: true,
+            ..test_config()
+        };
+        assert_eq!(
+            "https://gitlab.com/takac/side-project/git-open/-/blob/main/src/main.rs",
+            remote_url_to_repo_url(url, &state, &config).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_simple_repo_link_with_branch() {
+        let remote_urls = &[
+            "ssh://git@github.com/takac/git-open",
+            "https://github.com/takac/git-open",
+            "git@github.com:takac/git-open",
+        ];
+        let state = GrowseState {
+            branch: "master".to_string(),
+            ..generate_test_state()
+        };
+
+        let config = GrowseConfig {
+            use_branch: true,
+            ..test_config()
+        };
+
+        for url in remote_urls {
+            let expected = "https://github.com/takac/git-open/tree/master";
+            assert_eq!(
+                expected,
+                remote_url_to_repo_url(url, &state, &config).unwrap()
+            );
+            assert_eq!(
+                expected,
+                remote_url_to_repo_url(&format!("{}.git", url), &state, &config).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_bb_repo_link_with_branch() {
+        let state = GrowseState {
+            branch: "master".to_string(),
+            ..generate_test_state()
+        };
+        let config = GrowseConfig {
+            use_branch: true,
+            ..test_config()
+        };
+
+        let expected = "https://bitbucket.company.com/projects/takac/repos/git-open/browse?at=refs%2Fheads%2Fmaster";
+        let url = "ssh://git@bitbucket.company.com:7999/takac/git-open.git";
+        assert_eq!(
+            expected,
+            remote_url_to_repo_url(url, &state, &config).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_simple_repo_link_with_path() {
+        let remote_urls = &[
+            "ssh://git@github.com/takac/git-open",
+            "https://github.com/takac/git-open",
+            "git@github.com:takac/git-open",
+        ];
+        let state = GrowseState {
+            branch: "main".to_string(),
+            path: Some("src/main.rs".to_string()),
+            ..generate_test_state()
+        };
+
+        for url in remote_urls {
+            let expected = "https://github.com/takac/git-open/blob/main/src/main.rs";
+            assert_eq!(
+                expected,
+                remote_url_to_repo_url(url, &state, &test_config()).unwrap()
+            );
+            assert_eq!(
+                expected,
+                remote_url_to_repo_url(&format!("{}.git", url), &state, &test_config()).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_simple_repo_link_with_path_not_at_root() {
+        let remote_urls = &[
+            "ssh://git@github.com/takac/git-open",
+            "https://github.com/takac/git-open",
+            "git@github.com:takac/git-open",
+        ];
+        let state = GrowseState {
+            branch: "main".to_string(),
+            path: Some("src/main.rs".to_string()),
+            ..generate_test_state()
+        };
+
+        for url in remote_urls {
+            let expected = "https://github.com/takac/git-open/blob/main/src/main.rs";
+            assert_eq!(
+                expected,
+                remote_url_to_repo_url(url, &state, &test_config()).unwrap()
+            );
+            assert_eq!(
+                expected,
+                remote_url_to_repo_url(&format!("{}.git", url), &state, &test_config()).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_simple_repo_link_with_path_line_nos() {
+        let remote_urls = &[
+            "ssh://git@github.com/takac/git-open",
+            "https://github.com/takac/git-open",
+            "git@github.com:takac/git-open",
+        ];
+        let state = GrowseState {
+            branch: "main".to_string(),
+            path: Some("src/main.rs".to_string()),
+            line_number: Some(10),
+            ..generate_test_state()
+        };
+
+        for url in remote_urls {
+            let expected = "https://github.com/takac/git-open/blob/main/src/main.rs#L10";
+            assert_eq!(
+                expected,
+                remote_url_to_repo_url(url, &state, &test_config()).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_repo_link_with_remote() {
+        let remote_urls = &[
+            "ssh://git@github.com/takac/git-open",
+            "https://github.com/takac/git-open",
+            "git@github.com:takac/git-open",
+        ];
+        let state = GrowseState {
+            branch: "main".to_string(),
+            path: Some("src/main.rs".to_string()),
+            line_number: Some(10),
+            ..generate_test_state()
+        };
+
+        for url in remote_urls {
+            let expected = "https://github.com/takac/git-open/blob/main/src/main.rs#L10";
+            assert_eq!(
+                expected,
+                remote_url_to_repo_url(url, &state, &test_config()).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_path() {
+        let cases = [
+            ("src/main.rs", ("src/main.rs", None, None)),
+            ("src/main.rs:10", ("src/main.rs", Some(10), None)),
+            ("src/main.rs:10-25", ("src/main.rs", Some(10), Some(25))),
+            ("src/main.rs:10-10", ("src/main.rs", Some(10), None)),
+            ("src/main.rs#L10", ("src/main.rs", Some(10), None)),
+            ("src/main.rs#L10-L25", ("src/main.rs", Some(10), Some(25))),
+            ("src/main.rs#L10-25", ("src/main.rs", Some(10), Some(25))),
+        ];
+        for (input, (path, line_number, end_line_number)) in cases {
+            assert_eq!(
+                (path.to_string(), line_number, end_line_number),
+                parse_path(input).unwrap()
+            );
+        }
+        let err = parse_path("src/main.rs:25-10").unwrap_err();
+        assert!(matches!(err, GrowseError::InvalidPath { .. }));
+        assert_eq!(2, err.exit_code());
+    }
+
+    #[test]
+    fn test_repo_link_with_path_line_range() {
+        let state = GrowseState {
+            branch: "main".to_string(),
+            path: Some("src/main.rs".to_string()),
+            line_number: Some(10),
+            end_line_number: Some(25),
+            ..generate_test_state()
+        };
+        default_test_with_state(
+            &state,
+            HashMap::from([
+                (
+                    "https://github.com/takac/git-open/blob/main/src/main.rs#L10-L25",
+                    "git@github.com:takac/git-open.git",
+                ),
+                (
+                    "https://gitlab.com/takac/side-project/git-open/-/blob/main/src/main.rs#L10-25",
+                    "git@gitlab.com:takac/side-project/git-open.git",
+                ),
+                (
+                    "https://bitbucket.company.com/projects/takac/repos/git-open/browse/src/main.rs#10-25",
+                    "ssh://git@bitbucket.company.com:7999/takac/git-open.git",
+                ),
+            ]),
+        );
+    }
+
+    #[test]
+    fn test_repo_link_with_commit() {
+        let state = GrowseState {
+            path: Some("src/main.rs".to_string()),
+            line_number: Some(10),
+            commit: Some("3f4c5a2b9d0e1f6a7b8c9d0e1f2a3b4c5d6e7f80".to_string()),
+            ..generate_test_state()
+        };
+        default_test_with_state(
+            &state,
+            HashMap::from([
+                (
+                    "https://github.com/takac/git-open/blob/3f4c5a2b9d0e1f6a7b8c9d0e1f2a3b4c5d6e7f80/src/main.rs#L10",
+                    "git@github.com:takac/git-open.git",
+                ),
+                (
+                    "https://gitlab.com/takac/side-project/git-open/-/blob/3f4c5a2b9d0e1f6a7b8c9d0e1f2a3b4c5d6e7f80/src/main.rs#L10",
+                    "git@gitlab.com:takac/side-project/git-open.git",
+                ),
+                (
+                    "https://bitbucket.company.com/projects/takac/repos/git-open/browse/src/main.rs?at=3f4c5a2b9d0e1f6a7b8c9d0e1f2a3b4c5d6e7f80#10",
+                    "ssh://git@bitbucket.company.com:7999/takac/git-open.git",
+                ),
+            ]),
+        );
+    }
+
+    #[test]
+    fn test_pull_request_link() {
+        let state = GrowseState {
+            branch: "feature/x".to_string(),
+            link_kind: LinkKind::PullRequest,
+            ..generate_test_state()
+        };
+        default_test_with_state(
+            &state,
+            HashMap::from([
+                (
+                    "https://github.com/takac/git-open/pull/new/feature/x",
+                    "git@github.com:takac/git-open.git",
+                ),
+                (
+                    "https://gitlab.com/takac/side-project/git-open/-/merge_requests/new?merge_request%5Bsource_branch%5D=feature%2Fx",
+                    "git@gitlab.com:takac/side-project/git-open.git",
+                ),
+                (
+                    "https://bitbucket.company.com/projects/takac/repos/git-open/pull-requests?create&sourceBranch=refs%2Fheads%2Ffeature%2Fx",
+                    "ssh://git@bitbucket.company.com:7999/takac/git-open.git",
+                ),
+            ]),
+        );
+    }
+
+    #[test]
+    fn test_commit_link() {
+        let state = GrowseState {
+            commit: Some("3f4c5a2b9d0e1f6a7b8c9d0e1f2a3b4c5d6e7f80".to_string()),
+            link_kind: LinkKind::Commit,
+            ..generate_test_state()
+        };
+        default_test_with_state(
+            &state,
+            HashMap::from([
+                (
+                    "https://github.com/takac/git-open/commit/3f4c5a2b9d0e1f6a7b8c9d0e1f2a3b4c5d6e7f80",
+                    "git@github.com:takac/git-open.git",
+                ),
+                (
+                    "https://gitlab.com/takac/side-project/git-open/-/commit/3f4c5a2b9d0e1f6a7b8c9d0e1f2a3b4c5d6e7f80",
+                    "git@gitlab.com:takac/side-project/git-open.git",
+                ),
+                (
+                    "https://bitbucket.company.com/projects/takac/repos/git-open/commits/3f4c5a2b9d0e1f6a7b8c9d0e1f2a3b4c5d6e7f80",
+                    "ssh://git@bitbucket.company.com:7999/takac/git-open.git",
+                ),
+            ]),
+        );
+    }
+
+    #[test]
+    fn test_compare_link() {
+        let state = GrowseState {
+            compare: Some((
+                "refs/tags/v1.2.0".to_string(),
+                "refs/heads/main".to_string(),
+            )),
+            link_kind: LinkKind::Compare,
+            ..generate_test_state()
+        };
+        default_test_with_state(
+            &state,
+            HashMap::from([
+                (
+                    "https://github.com/takac/git-open/compare/v1.2.0...main",
+                    "git@github.com:takac/git-open.git",
+                ),
+                (
+                    "https://gitlab.com/takac/side-project/git-open/-/compare/v1.2.0...main",
+                    "git@gitlab.com:takac/side-project/git-open.git",
+                ),
+                (
+                    "https://bitbucket.company.com/projects/takac/repos/git-open/compare/diff?sourceBranch=refs%2Fheads%2Fmain&targetBranch=refs%2Ftags%2Fv1.2.0",
+                    "ssh://git@bitbucket.company.com:7999/takac/git-open.git",
+                ),
+            ]),
+        );
+    }
+
+    #[test]
+    fn test_blame_link() {
+        let state = GrowseState {
+            branch: "main".to_string(),
+            path: Some("src/main.rs".to_string()),
+            line_number: Some(10),
+            end_line_number: Some(25),
+            link_kind: LinkKind::Blame,
+            ..generate_test_state()
+        };
+        default_test_with_state(
+            &state,
+            HashMap::from([
+                (
+                    "https://github.com/takac/git-open/blame/main/src/main.rs#L10-L25",
+                    "git@github.com:takac/git-open.git",
+                ),
+                (
+                    "https://gitlab.com/takac/side-project/git-open/-/blame/main/src/main.rs#L10-25",
+                    "git@gitlab.com:takac/side-project/git-open.git",
+                ),
+                (
+                    "https://bitbucket.company.com/projects/takac/repos/git-open/browse/src/main.rs?at=refs%2Fheads%2Fmain&blame=true#10-25",
+                    "ssh://git@bitbucket.company.com:7999/takac/git-open.git",
+                ),
+            ]),
+        );
+
+        let state = GrowseState {
+            line_number: None,
+            end_line_number: None,
+            ..state
+        };
+        default_test_with_state(
+            &state,
+            HashMap::from([(
+                "https://github.com/takac/git-open/blame/main/src/main.rs",
+                "git@github.com:takac/git-open.git",
+            )]),
+        );
+    }
+
+    #[test]
+    fn test_history_link() {
+        let state = GrowseState {
+            branch: "main".to_string(),
+            path: Some("src/main.rs".to_string()),
+            link_kind: LinkKind::History,
+            ..generate_test_state()
+        };
+        default_test_with_state(
+            &state,
+            HashMap::from([
+                (
+                    "https://github.com/takac/git-open/commits/main/src/main.rs",
+                    "git@github.com:takac/git-open.git",
+                ),
+                (
+                    "https://gitlab.com/takac/side-project/git-open/-/commits/main/src/main.rs",
+                    "git@gitlab.com:takac/side-project/git-open.git",
+                ),
+                (
+                    "https://bitbucket.company.com/projects/takac/repos/git-open/commits?until=refs%2Fheads%2Fmain&path=src%2Fmain.rs",
+                    "ssh://git@bitbucket.company.com:7999/takac/git-open.git",
+                ),
+            ]),
+        );
+
+        let state = GrowseState {
+            path: None,
+            ..state
+        };
+        default_test_with_state(
+            &state,
+            HashMap::from([
+                (
+                    "https://github.com/takac/git-open/commits/main",
+                    "git@github.com:takac/git-open.git",
+                ),
+                (
+                    "https://bitbucket.company.com/projects/takac/repos/git-open/commits?until=refs%2Fheads%2Fmain",
+                    "ssh://git@bitbucket.company.com:7999/takac/git-open.git",
+                ),
+            ]),
+        );
+    }
+
+    #[test]
+    fn test_raw_link() {
+        let state = GrowseState {
+            branch: "main".to_string(),
+            path: Some("src/main.rs".to_string()),
+            line_number: Some(10),
+            link_kind: LinkKind::Raw,
+            ..generate_test_state()
+        };
+        default_test_with_state(
+            &state,
+            HashMap::from([
+                (
+                    "https://raw.githubusercontent.com/takac/git-open/main/src/main.rs",
+                    "git@github.com:takac/git-open.git",
+                ),
+                (
+                    "https://github.company.com/takac/git-open/raw/main/src/main.rs",
+                    "git@github.company.com:takac/git-open.git",
+                ),
+                (
+                    "https://gitlab.com/takac/side-project/git-open/-/raw/main/src/main.rs",
+                    "git@gitlab.com:takac/side-project/git-open.git",
+                ),
+                (
+                    "https://bitbucket.company.com/projects/takac/repos/git-open/raw/src/main.rs?at=refs%2Fheads%2Fmain",
+                    "ssh://git@bitbucket.company.com:7999/takac/git-open.git",
+                ),
+            ]),
+        );
+    }
+
+    #[test]
+    fn test_gitea_repo_link() {
+        default_test(HashMap::from([
+            (
+                "https://codeberg.org/takac/git-open",
+                "git@codeberg.org:takac/git-open.git",
+            ),
+            (
+                "https://gitea.company.com/takac/git-open",
+                "https://gitea.company.com/takac/git-open.git",
+            ),
+        ]));
+    }
+
+    #[test]
+    fn test_gitea_links() {
+        let url = "ssh://git@codeberg.org/takac/git-open.git";
+        let cases = [
+            (
+                GrowseState {
+                    branch: "main".to_string(),
+                    path: Some("src/main.rs".to_string()),
+                    line_number: Some(10),
+                    end_line_number: Some(20),
+                    ..generate_test_state()
+                },
+                "https://codeberg.org/takac/git-open/src/branch/main/src/main.rs#L10-L20",
+            ),
+            (
+                GrowseState {
+                    path: Some("src/main.rs".to_string()),
+                    commit: Some("3f4c5a2b".to_string()),
+                    ..generate_test_state()
+                },
+                "https://codeberg.org/takac/git-open/src/commit/3f4c5a2b/src/main.rs",
+            ),
+            (
+                GrowseState {
+                    commit: Some("3f4c5a2b".to_string()),
+                    link_kind: LinkKind::Commit,
+                    ..generate_test_state()
+                },
+                "https://codeberg.org/takac/git-open/commit/3f4c5a2b",
+            ),
+            (
+                GrowseState {
+                    compare: Some(("refs/heads/main".to_string(), "refs/heads/dev".to_string())),
+                    link_kind: LinkKind::Compare,
+                    ..generate_test_state()
+                },
+                "https://codeberg.org/takac/git-open/compare/main...dev",
+            ),
+            (
+                GrowseState {
+                    branch: "dev".to_string(),
+                    link_kind: LinkKind::PullRequest,
+                    ..generate_test_state()
+                },
+                "https://codeberg.org/takac/git-open/compare/dev",
+            ),
+        ];
+        for (state, expected) in cases {
+            assert_eq!(
+                expected,
+                remote_url_to_repo_url(url, &state, &test_config()).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_sourcehut_repo_link() {
+        let remote_urls = &[
+            "git@git.sr.ht:~takac/git-open",
+            "https://git.sr.ht/~takac/git-open",
+            "ssh://git@git.sr.ht/~takac/git-open",
+            "https://git.sr.ht/%7Etakac/git-open",
+        ];
+        for url in remote_urls {
+            assert_eq!(
+                "https://git.sr.ht/~takac/git-open",
+                remote_url_to_repo_url(url, &generate_test_state(), &test_config()).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_sourcehut_links() {
+        let url = "git@git.sr.ht:~takac/git-open";
+        let cases = [
+            (
+                GrowseState {
+                    branch: "main".to_string(),
+                    path: Some("src/main.rs".to_string()),
+                    line_number: Some(10),
+                    ..generate_test_state()
+                },
+                "https://git.sr.ht/~takac/git-open/tree/main/item/src/main.rs#L10",
+            ),
+            (
+                GrowseState {
+                    commit: Some("3f4c5a2b".to_string()),
+                    link_kind: LinkKind::Commit,
+                    ..generate_test_state()
+                },
+                "https://git.sr.ht/~takac/git-open/commit/3f4c5a2b",
+            ),
+            (
+                GrowseState {
+                    branch: "main".to_string(),
+                    path: Some("src/main.rs".to_string()),
+                    link_kind: LinkKind::History,
+                    ..generate_test_state()
+                },
+                "https://git.sr.ht/~takac/git-open/log/main/item/src/main.rs",
+            ),
+        ];
+        for (state, expected) in cases {
+            assert_eq!(
+                expected,
+                remote_url_to_repo_url(url, &state, &test_config()).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_azure_repo_link() {
+        let remote_urls = &[
+            "git@ssh.dev.azure.com:v3/takac/tools/git-open",
+            "https://takac@dev.azure.com/takac/tools/_git/git-open",
+        ];
+        for url in remote_urls {
+            assert_eq!(
+                "https://dev.azure.com/takac/tools/_git/git-open",
+                remote_url_to_repo_url(url, &generate_test_state(), &test_config()).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_azure_links() {
+        let url = "git@ssh.dev.azure.com:v3/takac/tools/git-open";
+        let cases = [
+            (
+                GrowseState {
+                    branch: "main".to_string(),
+                    ..generate_test_state()
+                },
+                true,
+                "https://dev.azure.com/takac/tools/_git/git-open?version=GBmain",
+            ),
+            (
+                GrowseState {
+                    branch: "main".to_string(),
+                    path: Some("src/main.rs".to_string()),
+                    ..generate_test_state()
+                },
+                false,
+                "https://dev.azure.com/takac/tools/_git/git-open?path=%2Fsrc%2Fmain.rs&version=GBmain",
+            ),
+            (
+                GrowseState {
+                    branch: "main".to_string(),
+                    path: Some("src/main.rs".to_string()),
+                    line_number: Some(10),
+                    ..generate_test_state()
+                },
+                false,
+                "https://dev.azure.com/takac/tools/_git/git-open?path=%2Fsrc%2Fmain.rs&version=GBmain&line=10&lineEnd=10&lineStartColumn=1",
+            ),
+            (
+                GrowseState {
+                    path: Some("src/main.rs".to_string()),
+                    line_number: Some(10),
+                    end_line_number: Some(25),
+                    commit: Some("3f4c5a2b".to_string()),
+                    ..generate_test_state()
+                },
+                false,
+                "https://dev.azure.com/takac/tools/_git/git-open?path=%2Fsrc%2Fmain.rs&version=GC3f4c5a2b&line=10&lineEnd=25&lineStartColumn=1",
+            ),
+            (
+                GrowseState {
+                    commit: Some("3f4c5a2b".to_string()),
+                    link_kind: LinkKind::Commit,
+                    ..generate_test_state()
+                },
+                false,
+                "https://dev.azure.com/takac/tools/_git/git-open/commit/3f4c5a2b",
+            ),
+        ];
+        for (state, use_branch, expected) in cases {
+            let config = GrowseConfig {
+                use_branch,
+                ..test_config()
+            };
+            assert_eq!(
+                expected,
+                remote_url_to_repo_url(url, &state, &config).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_bb_cloud_links() {
+        let url = "git@bitbucket.org:takac/git-open.git";
+        let cases = [
+            (
+                generate_test_state(),
+                "https://bitbucket.org/takac/git-open",
+            ),
+            (
+                GrowseState {
+                    branch: "main".to_string(),
+                    path: Some("src/main.rs".to_string()),
+                    ..generate_test_state()
+                },
+                "https://bitbucket.org/takac/git-open/src/main/src/main.rs",
+            ),
+            (
+                GrowseState {
+                    branch: "main".to_string(),
+                    path: Some("src/main.rs".to_string()),
+                    line_number: Some(10),
+                    ..generate_test_state()
+                },
+                "https://bitbucket.org/takac/git-open/src/main/src/main.rs#lines-10",
+            ),
+            (
+                GrowseState {
+                    branch: "main".to_string(),
+                    path: Some("src/main.rs".to_string()),
+                    line_number: Some(10),
+                    end_line_number: Some(25),
+                    ..generate_test_state()
+                },
+                "https://bitbucket.org/takac/git-open/src/main/src/main.rs#lines-10:25",
+            ),
+        ];
+        for (state, expected) in cases {
+            assert_eq!(
+                expected,
+                remote_url_to_repo_url(url, &state, &test_config()).unwrap()
+            );
+        }
+
+        let state = GrowseState {
+            branch: "feature/x".to_string(),
+            ..generate_test_state()
+        };
+        let config = GrowseConfig {
+            use_branch: true,
+            ..test_config()
+        };
+        assert_eq!(
+            "https://bitbucket.org/takac/git-open/src/feature/x",
+            remote_url_to_repo_url("https://bitbucket.org/takac/git-open.git", &state, &config)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_bb_server_configured_host() {
+        let config_file: GrowseConfigFile = toml::from_str(
+            r#"
+            [hosts."git.corp.example"]
+            backend = "bitbucket-server"
+            "#,
+        )
+        .unwrap();
+        let config = GrowseConfig {
+            hosts: config_file.hosts,
+            ..test_config()
+        };
+        assert_eq!(
+            "https://git.corp.example/projects/takac/repos/git-open",
+            remote_url_to_repo_url(
+                "ssh://git@git.corp.example:2222/takac/git-open.git",
+                &generate_test_state(),
+                &config
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_gerrit_links() {
+        let url = "ssh://takac@review.example.com:29418/platform/git-open";
+        let cases = [
+            (
+                generate_test_state(),
+                "https://review.example.com/plugins/gitiles/platform/git-open",
+            ),
+            (
+                GrowseState {
+                    branch: "main".to_string(),
+                    path: Some("src/main.rs".to_string()),
+                    line_number: Some(10),
+                    ..generate_test_state()
+                },
+                "https://review.example.com/plugins/gitiles/platform/git-open/+/refs/heads/main/src/main.rs#10",
+            ),
+            (
+                GrowseState {
+                    change_id: Some("I8473b95934b5732ac55d26311a706c9c2bde9940".to_string()),
+                    link_kind: LinkKind::PullRequest,
+                    ..generate_test_state()
+                },
+                "https://review.example.com/q/I8473b95934b5732ac55d26311a706c9c2bde9940",
+            ),
+        ];
+        for (state, expected) in cases {
+            assert_eq!(
+                expected,
+                remote_url_to_repo_url(url, &state, &test_config()).unwrap()
+            );
+        }
+
+        let state = GrowseState {
+            link_kind: LinkKind::PullRequest,
+            ..generate_test_state()
+        };
+        assert!(remote_url_to_repo_url(url, &state, &test_config()).is_err());
+    }
+
+    #[test]
+    fn test_gitiles_links() {
+        let url = "https://android.googlesource.com/a/platform/git-open";
+        let state = GrowseState {
+            commit: Some("3f4c5a2b".to_string()),
+            link_kind: LinkKind::Commit,
+            ..generate_test_state()
+        };
+        assert_eq!(
+            "https://android.googlesource.com/platform/git-open/+/3f4c5a2b",
+            remote_url_to_repo_url(url, &state, &test_config()).unwrap()
+        );
+
+        let state = GrowseState {
+            change_id: Some("I8473b95934b5732ac55d26311a706c9c2bde9940".to_string()),
+            link_kind: LinkKind::PullRequest,
+            ..generate_test_state()
+        };
+        assert_eq!(
+            "https://android-review.googlesource.com/q/I8473b95934b5732ac55d26311a706c9c2bde9940",
+            remote_url_to_repo_url(url, &state, &test_config()).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parse_change_id() {
+        let message = "Fix the thing\n\nLonger description.\n\nBug: 1234\nChange-Id: I8473b95934b5732ac55d26311a706c9c2bde9940\n";
+        assert_eq!(
+            Some("I8473b95934b5732ac55d26311a706c9c2bde9940".to_string()),
+            parse_change_id(message)
+        );
+        assert_eq!(None, parse_change_id("Fix the thing\n"));
+    }
+
+    #[test]
+    fn test_link_for() {
+        let repo_dir =
+            std::env::temp_dir().join(format!("growse-test-repo-{}", std::process::id()));
+        let repo = Repository::init(&repo_dir).unwrap();
+        repo.remote("origin", "git@github.com:takac/growse.git")
+            .unwrap();
+        let signature = git2::Signature::now("growse", "growse@example.com").unwrap();
+        let tree = repo
+            .find_tree(repo.index().unwrap().write_tree().unwrap())
+            .unwrap();
+        let commit = repo
+            .commit(
+                Some("HEAD"),
+                &signature,
+                &signature,
+                "Initial commit",
+                &tree,
+                &[],
+            )
+            .unwrap();
+        let branch = repo.head().unwrap().shorthand().unwrap().to_string();
+
+        let link = |file, lines, options| link_for(&repo_dir, file, lines, &options);
+        let cases = [
+            (
+                link(None, None, LinkOptions::default()),
+                "https://github.com/takac/growse".to_string(),
+            ),
+            (
+                link(
+                    Some("src/lib.rs"),
+                    Some((10, Some(20))),
+                    LinkOptions {
+                        config: GrowseConfig {
+                            current_branch: true,
+                            ..Default::default()
+                        },
+                        ..Default::default()
+                    },
+                ),
+                format!("https://github.com/takac/growse/blob/{branch}/src/lib.rs#L10-L20"),
+            ),
+            (
+                link(
+                    Some("src/lib.rs"),
+                    None,
+                    LinkOptions {
+                        commit: Some("HEAD".to_string()),
+                        ..Default::default()
+                    },
+                ),
+                format!("https://github.com/takac/growse/blob/{commit}/src/lib.rs"),
+            ),
+        ];
+        let no_remote = link(
+            None,
+            None,
+            LinkOptions {
+                remote: Some("upstream".to_string()),
+                ..Default::default()
+            },
+        );
+        let invalid_revision = link(
+            None,
+            None,
+            LinkOptions {
+                commit: Some("nosuchrev".to_string()),
+                link_kind: LinkKind::Commit,
+                ..Default::default()
+            },
+        );
+        std::fs::remove_dir_all(&repo_dir).unwrap();
+
+        for (url, expected) in cases {
+            assert_eq!(expected, url.unwrap().as_str());
+        }
+        let no_remote = no_remote.unwrap_err();
+        assert!(matches!(no_remote, GrowseError::NoRemote(Some(_))));
+        assert_eq!(4, no_remote.exit_code());
+        let invalid_revision = invalid_revision.unwrap_err();
+        assert!(matches!(
+            invalid_revision,
+            GrowseError::InvalidRevision { .. }
+        ));
+        assert_eq!(2, invalid_revision.exit_code());
+    }
+
+    #[test]
+    fn test_environment_errors() {
+        let dir = std::env::temp_dir().join(format!("growse-test-no-repo-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let no_repository = link_for(&dir, None, None, &LinkOptions::default());
+        let config_read = GrowseConfig::from_file(&dir);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let no_repository = no_repository.unwrap_err();
+        assert!(matches!(no_repository, GrowseError::NoRepository(_)));
+        assert_eq!(4, no_repository.exit_code());
+        let config_read = config_read.unwrap_err();
+        assert!(matches!(config_read, GrowseError::ConfigRead { .. }));
+        assert_eq!(3, config_read.exit_code());
+    }
+
+    #[test]
+    fn test_link_for_insteadof_remote() {
+        let repo_dir =
+            std::env::temp_dir().join(format!("growse-test-insteadof-{}", std::process::id()));
+        let repo = Repository::init(&repo_dir).unwrap();
+        repo.config()
+            .unwrap()
+            .set_str("url.git@github.com:.insteadOf", "gh:")
+            .unwrap();
+        repo.remote("origin", "gh:takac/growse").unwrap();
+
+        let url = link_for(&repo_dir, None, None, &LinkOptions::default());
+        std::fs::remove_dir_all(&repo_dir).unwrap();
+
+        assert_eq!("https://github.com/takac/growse", url.unwrap().as_str());
+    }
+
+    #[test]
+    fn test_link_for_pushinsteadof_remote() {
+        let repo_dir =
+            std::env::temp_dir().join(format!("growse-test-pushinsteadof-{}", std::process::id()));
+        let repo = Repository::init(&repo_dir).unwrap();
+        repo.config()
+            .unwrap()
+            .set_str("url.git@github.com:.pushInsteadOf", "gh:")
+            .unwrap();
+        repo.remote("origin", "gh:takac/growse").unwrap();
+
+        let url = link_for(&repo_dir, None, None, &LinkOptions::default());
+        std::fs::remove_dir_all(&repo_dir).unwrap();
+
+        assert_eq!("https://github.com/takac/growse", url.unwrap().as_str());
+    }
+
+    #[test]
+    fn test_ssh_config_aliases() {
+        let ssh_dir = std::env::temp_dir().join(format!("growse-test-ssh-{}", std::process::id()));
+        std::fs::create_dir_all(ssh_dir.join("config.d")).unwrap();
+        std::fs::write(
+            ssh_dir.join("config.d").join("work"),
+            "Host gitlab-work\n    HostName gitlab.com\n",
+        )
+        .unwrap();
+        let ssh_config = SshConfig::parse(
+            r#"
+# keys per account
+Host github-work github-personal
+    HostName github.com
+    IdentityFile ~/.ssh/id_work
+
+Host *.corp !bastion.corp
+    HostName=%h.example.com
+
+Include config.d/*
+
+# ssh over port 443
+Host github.com
+    HostName ssh.github.com
+    Port 443
+
+Host gitlab.com
+    HostName altssh.gitlab.com
+    Port 443
+
+Host *
+    User git
+"#,
+            &ssh_dir,
+        );
+        std::fs::remove_dir_all(&ssh_dir).unwrap();
+
+        assert_eq!(
+            Some("github.com".to_string()),
+            ssh_config.hostname("github-work")
+        );
+        assert_eq!(
+            Some("github.com".to_string()),
+            ssh_config.hostname("GitHub-Personal")
+        );
+        assert_eq!(
+            Some("gitlab.com".to_string()),
+            ssh_config.hostname("gitlab-work")
+        );
+        assert_eq!(
+            Some("git.corp.example.com".to_string()),
+            ssh_config.hostname("git.corp")
+        );
+        assert_eq!(None, ssh_config.hostname("bastion.corp"));
+        assert_eq!(None, ssh_config.hostname("bitbucket.org"));
+        assert_eq!(
+            Some("ssh.github.com".to_string()),
+            ssh_config.hostname("github.com")
+        );
+
+        let config = GrowseConfig {
+            ssh_config,
+            ..test_config()
+        };
+        let cases = [
+            (
+                "git@github-work:takac/growse.git",
+                "https://github.com/takac/growse",
+            ),
+            (
+                "ssh://git@gitlab-work/takac/growse.git",
+                "https://gitlab.com/takac/growse",
+            ),
+            // aliases only apply to ssh
+            (
+                "https://github.com/takac/growse.git",
+                "https://github.com/takac/growse",
+            ),
+            // known hosts keep their name over an ssh only HostName
+            (
+                "git@github.com:takac/growse.git",
+                "https://github.com/takac/growse",
+            ),
+            (
+                "git@gitlab.com:takac/growse.git",
+                "https://gitlab.com/takac/growse",
+            ),
+        ];
+        for (url, expected) in cases {
+            assert_eq!(
+                expected,
+                remote_url_to_repo_url(url, &generate_test_state(), &config).unwrap()
+            );
+        }
+    }
+
+    fn config_with_backend(host: &str, backend: &str) -> GrowseConfig {
+        GrowseConfig {
+            hosts: HashMap::from([(
+                host.to_string(),
+                HostConfig {
+                    backend: Some(backend.to_string()),
+                    ..Default::default()
+                },
+            )]),
+            ..test_config()
+        }
+    }
+
+    #[test]
+    fn test_cgit_links() {
+        let url = "https://git.example.com/pub/scm/git-open.git";
+        let config = config_with_backend("git.example.com", "cgit");
+        let cases = [
+            (
+                GrowseState {
+                    branch: "main".to_string(),
+                    path: Some("src/main.rs".to_string()),
+                    line_number: Some(10),
+                    ..generate_test_state()
+                },
+                "https://git.example.com/pub/scm/git-open.git/tree/src/main.rs?h=main#n10",
+            ),
+            (
+                GrowseState {
+                    commit: Some("3f4c5a2b".to_string()),
+                    link_kind: LinkKind::Commit,
+                    ..generate_test_state()
+                },
+                "https://git.example.com/pub/scm/git-open.git/commit/?id=3f4c5a2b",
+            ),
+            (
+                GrowseState {
+                    path: Some("src/main.rs".to_string()),
+                    commit: Some("3f4c5a2b".to_string()),
+                    link_kind: LinkKind::Raw,
+                    ..generate_test_state()
+                },
+                "https://git.example.com/pub/scm/git-open.git/plain/src/main.rs?id=3f4c5a2b",
+            ),
+        ];
+        for (state, expected) in cases {
+            assert_eq!(
+                expected,
+                remote_url_to_repo_url(url, &state, &config).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_gitweb_links() {
+        let url = "git@git.example.com:projects/git-open.git";
+        let config = config_with_backend("git.example.com", "gitweb");
+        let cases = [
+            (
+                generate_test_state(),
+                "https://git.example.com/?p=projects/git-open.git",
+            ),
+            (
+                GrowseState {
+                    branch: "main".to_string(),
+                    path: Some("src/main.rs".to_string()),
+                    line_number: Some(10),
+                    ..generate_test_state()
+                },
+                "https://git.example.com/?p=projects/git-open.git;a=blob;f=src/main.rs;hb=main#l10",
+            ),
+            (
+                GrowseState {
+                    commit: Some("3f4c5a2b".to_string()),
+                    link_kind: LinkKind::Commit,
+                    ..generate_test_state()
+                },
+                "https://git.example.com/?p=projects/git-open.git;a=commit;h=3f4c5a2b",
+            ),
+        ];
+        for (state, expected) in cases {
+            assert_eq!(
+                expected,
+                remote_url_to_repo_url(url, &state, &config).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_codecommit_repo_link() {
+        let remote_urls = &[
+            "https://git-codecommit.eu-west-1.amazonaws.com/v1/repos/git-open",
+            "ssh://git-codecommit.eu-west-1.amazonaws.com/v1/repos/git-open",
+            "ssh://APKAEIBAERJR2EXAMPLE@git-codecommit.eu-west-1.amazonaws.com/v1/repos/git-open",
+            "codecommit::eu-west-1://git-open",
+            "codecommit::eu-west-1://takac@git-open",
+        ];
+        for url in remote_urls {
+            assert_eq!(
+                "https://eu-west-1.console.aws.amazon.com/codesuite/codecommit/repositories/git-open/browse?region=eu-west-1",
+                remote_url_to_repo_url(url, &generate_test_state(), &test_config()).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_codecommit_links() {
+        let url = "codecommit::us-east-1://git-open";
+        let cases = [
+            (
+                GrowseState {
+                    branch: "main".to_string(),
+                    path: Some("src/main.rs".to_string()),
+                    ..generate_test_state()
+                },
+                "https://us-east-1.console.aws.amazon.com/codesuite/codecommit/repositories/git-open/browse/refs/heads/main/--/src/main.rs?region=us-east-1",
+            ),
+            (
+                GrowseState {
+                    branch: "main".to_string(),
+                    path: Some("src/main.rs".to_string()),
+                    line_number: Some(10),
+                    end_line_number: Some(25),
+                    ..generate_test_state()
+                },
+                "https://us-east-1.console.aws.amazon.com/codesuite/codecommit/repositories/git-open/browse/refs/heads/main/--/src/main.rs?region=us-east-1&lines=10-25",
+            ),
+            (
+                GrowseState {
+                    commit: Some("3f4c5a2b".to_string()),
+                    link_kind: LinkKind::Commit,
+                    ..generate_test_state()
+                },
+                "https://us-east-1.console.aws.amazon.com/codesuite/codecommit/repositories/git-open/commit/3f4c5a2b?region=us-east-1",
+            ),
+        ];
+        for (state, expected) in cases {
+            assert_eq!(
+                expected,
+                remote_url_to_repo_url(url, &state, &test_config()).unwrap()
+            );
+        }
+
+        let state = GrowseState {
+            branch: "dev".to_string(),
+            ..generate_test_state()
+        };
+        let config = GrowseConfig {
+            use_branch: true,
+            ..test_config()
+        };
+        assert_eq!(
+            "https://us-east-1.console.aws.amazon.com/codesuite/codecommit/repositories/git-open/browse/refs/heads/dev?region=us-east-1",
+            remote_url_to_repo_url(url, &state, &config).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_encoded_paths() {
+        let state = GrowseState {
+            branch: "main".to_string(),
+            path: Some("src/a b#c.rs".to_string()),
+            line_number: Some(3),
+            ..generate_test_state()
+        };
+        let cases = [
+            (
+                "git@github.com:takac/git-open.git",
+                test_config(),
+                "https://github.com/takac/git-open/blob/main/src/a%20b%23c.rs#L3",
+            ),
+            (
+                "git@gitlab.com:takac/git-open.git",
+                test_config(),
+                "https://gitlab.com/takac/git-open/-/blob/main/src/a%20b%23c.rs#L3",
+            ),
+            (
+                "ssh://git@bitbucket.company.com:7999/takac/git-open.git",
+                test_config(),
+                "https://bitbucket.company.com/projects/takac/repos/git-open/browse/src/a%20b%23c.rs#3",
+            ),
+            (
+                "codecommit::us-east-1://git-open",
+                test_config(),
+                "https://us-east-1.console.aws.amazon.com/codesuite/codecommit/repositories/git-open/browse/refs/heads/main/--/src/a%20b%23c.rs?region=us-east-1&lines=3-3",
+            ),
+            (
+                "https://git.example.com/pub/scm/git-open.git",
+                config_with_backend("git.example.com", "cgit"),
+                "https://git.example.com/pub/scm/git-open.git/tree/src/a%20b%23c.rs?h=main#n3",
+            ),
+            (
+                "git@git.example.com:projects/git-open.git",
+                config_with_backend("git.example.com", "gitweb"),
+                "https://git.example.com/?p=projects/git-open.git;a=blob;f=src/a%20b%23c.rs;hb=main#l3",
+            ),
+        ];
+        for (url, config, expected) in cases {
+            assert_eq!(
+                expected,
+                remote_url_to_repo_url(url, &state, &config).unwrap()
+            );
+        }
+
+        let state = GrowseState {
+            branch: "feat/#1".to_string(),
+            link_kind: LinkKind::PullRequest,
+            ..generate_test_state()
+        };
+        assert_eq!(
+            "https://github.com/takac/git-open/pull/new/feat/%231",
+            remote_url_to_repo_url("git@github.com:takac/git-open.git", &state, &test_config())
+                .unwrap()
+        );
+
+        // ; separates gitweb's parameters
+        let state = GrowseState {
+            branch: "feat;x".to_string(),
+            path: Some("a;b.rs".to_string()),
+            ..generate_test_state()
+        };
+        let config = GrowseConfig {
+            use_branch: true,
+            ..config_with_backend("git.example.com", "gitweb")
+        };
+        assert_eq!(
+            "https://git.example.com/?p=projects/git-open.git;a=blob;f=a%3Bb.rs;hb=feat%3Bx",
+            remote_url_to_repo_url("git@git.example.com:projects/git-open.git", &state, &config)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_configured_host_overrides() {
+        let config_file: GrowseConfigFile = toml::from_str(
+            r#"
+            [hosts."mygithubmirror.gitlab.internal"]
+            backend = "github"
+
+            [hosts."git-ssh.corp.example"]
+            backend = "gitlab"
+            hostname = "git.corp.example"
+            scheme = "http"
+            port = 8080
+
+            [hosts."github.com"]
+            hostname = "github.example.com"
+            "#,
+        )
+        .unwrap();
+        let config = GrowseConfig {
+            hosts: config_file.hosts,
+            ..test_config()
+        };
+        let state = GrowseState {
+            branch: "main".to_string(),
+            path: Some("src/main.rs".to_string()),
+            ..generate_test_state()
+        };
+        default_test_with_config(
+            &state,
+            &config,
+            HashMap::from([
+                (
+                    "https://mygithubmirror.gitlab.internal/takac/git-open/blob/main/src/main.rs",
+                    "git@mygithubmirror.gitlab.internal:takac/git-open.git",
+                ),
+                (
+                    "http://git.corp.example:8080/takac/side-project/git-open/-/blob/main/src/main.rs",
+                    "ssh://git@git-ssh.corp.example:2222/takac/side-project/git-open.git",
+                ),
+                (
+                    "https://github.example.com/takac/git-open/blob/main/src/main.rs",
+                    "git@github.com:takac/git-open.git",
+                ),
+            ]),
+        );
+    }
+
+    #[test]
+    fn test_template_links() {
+        let config_file: GrowseConfigFile = toml::from_str(
+            r#"
+            [hosts."git.corp.example".templates]
+            repo = "https://{host}/{owner}/{repo}"
+            branch = "https://{host}/{owner}/{repo}/src/{ref}"
+            file = "https://{host}/{owner}/{repo}/src/{ref}/{path}"
+            line = "https://{host}/{owner}/{repo}/src/{ref}/{path}#L{line}"
+            range = "{base_url}/{fullname}/src/{ref}/{path}#L{line}-{end_line}"
+            commit = "https://{host}/{owner}/{repo}/commit/{commit}"
+            compare = "https://{host}/{owner}/{repo}/compare/{base}..{head}"
+            "#,
+        )
+        .unwrap();
+        let config = GrowseConfig {
+            hosts: config_file.hosts,
+            ..test_config()
+        };
+        let url = "git@git.corp.example:takac/git-open.git";
+        let cases = [
+            (
+                generate_test_state(),
+                "https://git.corp.example/takac/git-open",
+            ),
+            (
+                GrowseState {
+                    branch: "feature/x y".to_string(),
+                    path: Some("src/my file.rs".to_string()),
+                    line_number: Some(10),
+                    ..generate_test_state()
+                },
+                "https://git.corp.example/takac/git-open/src/feature/x%20y/src/my%20file.rs#L10",
+            ),
+            (
+                GrowseState {
+                    branch: "main".to_string(),
+                    path: Some("src/main.rs".to_string()),
+                    line_number: Some(10),
+                    end_line_number: Some(25),
+                    ..generate_test_state()
+                },
+                "https://git.corp.example/takac/git-open/src/main/src/main.rs#L10-25",
+            ),
+            (
+                GrowseState {
+                    compare: Some((
+                        "refs/tags/v1.2.0".to_string(),
+                        "refs/heads/main".to_string(),
+                    )),
+                    link_kind: LinkKind::Compare,
+                    ..generate_test_state()
+                },
+                "https://git.corp.example/takac/git-open/compare/v1.2.0..main",
+            ),
+        ];
+        for (state, expected) in cases {
+            assert_eq!(
+                expected,
+                remote_url_to_repo_url(url, &state, &config).unwrap()
+            );
+        }
+
+        let state = GrowseState {
+            path: Some("src/main.rs".to_string()),
+            link_kind: LinkKind::Blame,
+            ..generate_test_state()
+        };
+        assert!(matches!(
+            remote_url_to_repo_url(url, &state, &config),
+            Err(GrowseError::Unsupported { .. })
+        ));
+    }
+
+    #[test]
+    fn test_invalid_remote() {
+        let err = remote_url_to_repo_url("codecommit:/", &generate_test_state(), &test_config())
+            .unwrap_err();
+        assert!(matches!(err, GrowseError::InvalidRemote { .. }));
+        assert_eq!(5, err.exit_code());
+    }
+
+    #[test]
+    fn test_provider_confidence() {
+        let cases = [
+            // a host label naming the service beats a mention
+            (
+                "git@mygithubmirror.gitlab.internal:takac/growse.git",
+                "https://mygithubmirror.gitlab.internal/takac/growse/-/blob/master/README.md",
+            ),
+            // named by both, GitHub is registered first
+            (
+                "git@gitlab.github.corp:takac/growse.git",
+                "https://gitlab.github.corp/takac/growse/blob/master/README.md",
+            ),
+            (
+                "git@gitlab-mirror.github.corp:takac/growse.git",
+                "https://gitlab-mirror.github.corp/takac/growse/blob/master/README.md",
+            ),
+            // the default ssh port of Bitbucket Server beats a mention of GitHub
+            (
+                "ssh://git@github-mirror.corp:7999/takac/growse.git",
+                "https://github-mirror.corp/projects/takac/repos/growse/browse/README.md",
+            ),
+        ];
+        let state = GrowseState {
+            path: Some("README.md".to_string()),
+            ..generate_test_state()
+        };
+        for (url, expected) in cases {
+            assert_eq!(
+                expected,
+                remote_url_to_repo_url(url, &state, &test_config()).unwrap()
+            );
+        }
+    }
+
+    struct MirrorProvider(i32);
+
+    impl Provider for MirrorProvider {
+        fn backends(&self) -> &[&str] {
+            &["mirror"]
+        }
+
+        fn confidence(&self, url: &GitUrl, _config: &GrowseConfig) -> Confidence {
+            Confidence::mentioned(url.host.as_deref().unwrap_or_default(), "github")
+        }
+
+        fn priority(&self) -> i32 {
+            self.0
+        }
+
+        fn repo<'a>(
+            &self,
+            url: &'a GitUrl,
+            config: &'a GrowseConfig,
+            state: &'a GrowseState,
+        ) -> Box<dyn Repo + 'a> {
+            generic::GenericProvider.repo(url, config, state)
+        }
+    }
+
+    #[test]
+    fn test_registry() {
+        let url = "git@github.corp.example:takac/growse.git";
+        let state = GrowseState {
+            path: Some("README.md".to_string()),
+            ..generate_test_state()
+        };
+
+        // ties go to the first registered provider unless one has a higher priority
+        let mut registry = Registry::default();
+        registry.register(Box::new(MirrorProvider(0)));
+        assert_eq!(
+            "https://github.corp.example/takac/growse/blob/master/README.md",
+            registry.link(url, &state, &test_config()).unwrap()
+        );
+        registry.register(Box::new(MirrorProvider(1)));
+        assert!(matches!(
+            registry.link(url, &state, &test_config()),
+            Err(GrowseError::Unsupported { .. })
+        ));
+
+        assert!(matches!(
+            Registry::new().link(url, &state, &test_config()),
+            Err(GrowseError::UnknownHost { .. })
+        ));
+    }
+
+    #[test]
+    fn test_registry_configured_provider() {
+        let url = "git@git.corp.example:takac/growse.git";
+        let config = config_with_backend("git.corp.example", "mirror");
+        let mut registry = Registry::default();
+        registry.register(Box::new(MirrorProvider(0)));
+        assert_eq!(
+            "https://git.corp.example/takac/growse",
+            registry.link(url, &generate_test_state(), &config).unwrap()
+        );
+
+        let err = remote_url_to_repo_url(url, &generate_test_state(), &config).unwrap_err();
+        assert!(matches!(err, GrowseError::UnknownBackend { .. }));
+        assert_eq!(3, err.exit_code());
+
+        let repo_dir =
+            std::env::temp_dir().join(format!("growse-test-registry-{}", std::process::id()));
+        let repo = Repository::init(&repo_dir).unwrap();
+        repo.remote("origin", url).unwrap();
+        let options = LinkOptions {
+            config,
+            ..Default::default()
+        };
+        let link = registry.link_for(&repo_dir, None, None, &options);
+        std::fs::remove_dir_all(&repo_dir).unwrap();
+
+        assert_eq!(
+            "https://git.corp.example/takac/growse",
+            link.unwrap().as_str()
+        );
+    }
+
+    #[test]
+    fn test_unknown_host() {
+        for url in [
+            "git@git.corp.example:takac/git-open.git",
+            "file:///srv/git/git-open.git",
+            "/srv/git/git-open.git",
+        ] {
+            let err =
+                remote_url_to_repo_url(url, &generate_test_state(), &test_config()).unwrap_err();
+            assert!(matches!(err, GrowseError::UnknownHost { .. }));
+            assert_eq!(5, err.exit_code());
+            assert!(err.to_string().contains(url));
+        }
+    }
+
+    #[test]
+    fn test_generic_fallback() {
+        let config = GrowseConfig {
+            generic_fallback: true,
+            ..test_config()
+        };
+        assert_eq!(
+            "https://git.corp.example/takac/git-open",
+            remote_url_to_repo_url(
+                "git@git.corp.example:takac/git-open.git",
+                &generate_test_state(),
+                &config
+            )
+            .unwrap()
+        );
+
+        let state = GrowseState {
+            path: Some("src/main.rs".to_string()),
+            ..generate_test_state()
+        };
+        let config = config_with_backend("git.corp.example", "generic");
+        let err =
+            remote_url_to_repo_url("git@git.corp.example:takac/git-open.git", &state, &config)
+                .unwrap_err();
+        assert!(matches!(err, GrowseError::Unsupported { .. }));
+        assert_eq!(6, err.exit_code());
+    }
+
+    #[test]
+    fn test_configured_host_backend() {
+        let config_file: GrowseConfigFile = toml::from_str(
+            r#"
+            [hosts."git.corp.example"]
+            backend = "forgejo"
+            "#,
+        )
+        .unwrap();
+        let config = GrowseConfig {
+            use_branch: true,
+            hosts: config_file.hosts,
+            ..test_config()
+        };
+        let state = GrowseState {
+            branch: "main".to_string(),
+            ..generate_test_state()
+        };
+        assert_eq!(
+            "https://git.corp.example/takac/git-open/src/branch/main",
+            remote_url_to_repo_url("git@git.corp.example:takac/git-open.git", &state, &config)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_load_config() {
+        let config: GrowseConfigFile = toml::from_str(
+            r#"
+            [growse]
+            verbose = true
+            generic_fallback = true
+            "#,
+        )
+        .unwrap();
+        assert!(config.growse.generic_fallback);
+    }
+}
@@ -1,20 +1,12 @@
-mod bitbucket;
-mod github;
-mod gitlab;
-
 use clap::CommandFactory;
 
 use clap::*;
 use clap_complete::*;
-use git2::{Remote, Repository};
-use git_url_parse::GitUrl;
-use regex::Regex;
+use git2::Repository;
+use growse::{link_for, parse_path, GrowseConfig, GrowseError, LinkKind, LinkOptions, SshConfig};
 use serde::Deserialize;
 use std::io;
-// use std::collections::HashMap;
-use duplicate::duplicate;
-use std::path::Path;
-use url::Url;
+use std::path::{Component, Path, PathBuf};
 
 #[derive(Parser)]
 #[command(author, version, about, group(ArgGroup::new("branch_group").args(&["current_branch", "branch"])))]
@@ -43,116 +35,85 @@ struct Cli {
 
     #[arg(short, long, group = "branch_group", action=ArgAction::SetTrue)]
     current_branch: Option<bool>,
+
+    // Pin links to the full commit SHA of the given revision (--commit=REV), defaults to HEAD
+    #[arg(
+        long,
+        visible_alias = "permalink",
+        value_name = "REV",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "HEAD",
+        conflicts_with = "branch_group"
+    )]
+    commit: Option<String>,
+
+    // Open the pull/merge request for the current branch, or for --branch if given
+    #[arg(long, action=ArgAction::SetTrue, conflicts_with_all = ["path", "commit"])]
+    pr: Option<bool>,
+
+    // Open the commit page for the given revision
+    #[arg(long, value_name = "REV", conflicts_with_all = ["path", "commit", "pr"])]
+    show: Option<String>,
+
+    // Open the compare view (--compare=BASE...HEAD), defaults to the remote default branch
+    // against the current branch
+    #[arg(
+        long,
+        value_name = "BASE...HEAD",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "",
+        conflicts_with_all = ["path", "commit", "pr", "show", "branch_group"]
+    )]
+    compare: Option<String>,
+
+    // Open the blame view of the file instead of the file itself
+    #[arg(
+        long,
+        action=ArgAction::SetTrue,
+        requires = "path",
+        conflicts_with_all = ["pr", "show", "compare"]
+    )]
+    blame: Option<bool>,
+
+    // Open the commit history of the file, or of the branch when no path is given
+    #[arg(
+        long,
+        visible_alias = "log",
+        action=ArgAction::SetTrue,
+        conflicts_with_all = ["pr", "show", "compare", "blame"]
+    )]
+    history: Option<bool>,
+
+    // Open the raw content of the file instead of the rendered page
+    #[arg(
+        long,
+        action=ArgAction::SetTrue,
+        requires = "path",
+        conflicts_with_all = ["pr", "show", "compare", "blame", "history"]
+    )]
+    raw: Option<bool>,
 }
 
 // TODO XDG_CONFIG_HOME
 const CONFIG_FILE: &str = "growse.toml";
 
-#[derive(Debug, Deserialize, Clone)]
-struct GrowseConfigFile {
-    growse: GrowseConfig,
-}
-
-#[derive(Debug, Deserialize, Clone)]
-struct GrowseConfig {
-    #[serde(default)]
-    use_branch: bool,
+// Settings of the [growse] table only the command line uses
+#[derive(Deserialize, Default)]
+struct Settings {
+    // Print the link instead of opening it
     #[serde(default)]
     no_show: bool,
+    // Show debug logs
     #[serde(default)]
     verbose: bool,
-    #[serde(default)]
-    current_branch: bool,
 }
 
-#[derive(Clone, Debug)]
-struct GrowseState {
-    path: Option<String>,
-    line_number: Option<u32>,
-    branch: String,
-}
-
-trait RepoUrler {
-    fn to_url(&self) -> Result<String, Box<dyn std::error::Error>>;
-}
-
-trait Repo {
-    fn is_host(&self) -> bool;
-    fn to_repo_url(&self) -> Result<String, Box<dyn std::error::Error>>;
-    fn to_repo_url_with_branch(&self) -> Result<String, Box<dyn std::error::Error>>;
-    fn to_repo_url_with_path(&self) -> Result<String, Box<dyn std::error::Error>>;
-    fn to_repo_url_with_path_and_branch(&self) -> Result<String, Box<dyn std::error::Error>>;
-    fn to_repo_url_with_path_and_line_number(&self) -> Result<String, Box<dyn std::error::Error>>;
-    fn to_repo_url_with_path_and_branch_and_line_number(
-        &self,
-    ) -> Result<String, Box<dyn std::error::Error>>;
-}
-
-duplicate! {
-    [ name; [BitBucket]; [GitHub]; [GitLab] ]
-    pub struct name {
-        url: GitUrl,
-        config: GrowseConfig,
-        state: GrowseState,
-    }
-    impl RepoUrler for name {
-
-        fn to_url(&self) -> Result<String, Box<dyn std::error::Error>> {
-            if self.config.use_branch {
-                if self.state.path.is_some() {
-                    if self.state.line_number.is_some() {
-                        self.to_repo_url_with_path_and_branch_and_line_number()
-                    } else {
-                        self.to_repo_url_with_path_and_branch()
-                    }
-                } else {
-                    self.to_repo_url_with_branch()
-                }
-            } else if self.state.path.is_some() {
-                if self.state.line_number.is_some() {
-                    self.to_repo_url_with_path_and_line_number()
-                } else {
-                    self.to_repo_url_with_path()
-                }
-            } else {
-                self.to_repo_url()
-            }
-        }
-    }
-}
-
-fn remote_url_to_repo_url(
-    url: &str,
-    state: &GrowseState,
-    config: &GrowseConfig,
-) -> Result<String, Box<dyn std::error::Error>> {
-    let url = GitUrl::parse(url)?;
-
-    let github = GitHub {
-        url: url.clone(),
-        config: config.clone(),
-        state: state.clone(),
-    };
-    let gitlab = GitLab {
-        url: url.clone(),
-        config: config.clone(),
-        state: state.clone(),
-    };
-    let bitbucket = BitBucket {
-        url,
-        config: config.clone(),
-        state: state.clone(),
-    };
-
-    if github.is_host() {
-        github.to_url()
-    } else if gitlab.is_host() {
-        gitlab.to_url()
-    } else if bitbucket.is_host() {
-        bitbucket.to_url()
-    } else {
-        panic!("Unknown host")
-    }
+#[derive(Deserialize)]
+struct SettingsFile {
+    #[serde(default)]
+    growse: Settings,
 }
 
 fn main() {
@@ -166,401 +127,216 @@ fn main() {
     }
     if let Err(e) = run(&cli) {
         eprintln!("Error: {}", e);
-        std::process::exit(1);
+        std::process::exit(e.exit_code());
     }
 }
 
-fn merge_config_cli(cli: &Cli, config: &GrowseConfig) -> GrowseConfig {
-    let mut config = config.clone();
-    config.current_branch = cli.current_branch.unwrap_or(false);
-    config.use_branch = cli.branch.is_some() || config.current_branch;
-    if cli.no_show.is_some() {
-        config.no_show = cli.no_show.unwrap();
+// Debug output for --verbose
+struct StderrLogger;
+
+impl log::Log for StderrLogger {
+    fn enabled(&self, _metadata: &log::Metadata) -> bool {
+        true
     }
-    if cli.verbose.is_some() {
-        config.verbose = cli.verbose.unwrap();
+
+    fn log(&self, record: &log::Record) {
+        eprintln!("{}", record.args());
     }
-    config
+
+    fn flush(&self) {}
 }
 
-fn config(cli: &Cli) -> Result<GrowseConfig, Box<dyn std::error::Error>> {
-    // use given config file
-    if let Some(config_file) = cli.config_file.as_ref() {
-        let config: GrowseConfigFile =
-            toml::from_str(&std::fs::read_to_string(cli.config_file.clone().unwrap())?)?;
-        if Path::new(config_file).exists() {
-            Ok(merge_config_cli(cli, &config.growse))
-        } else {
-            Err(format!("Config file {} not found", config_file).into())
+fn config(cli: &Cli) -> Result<(GrowseConfig, Settings), GrowseError> {
+    // use given config file, otherwise lookup default config file
+    let config_path = if let Some(config_file) = cli.config_file.as_ref() {
+        if !Path::new(config_file).exists() {
+            return Err(GrowseError::ConfigNotFound(config_file.into()));
         }
+        Some(PathBuf::from(config_file))
     } else {
-        // lookup default config file
-        let config_dir = dirs::config_dir().ok_or("No config dir found")?;
-        let config_path = config_dir.join(CONFIG_FILE);
-        if config_path.exists() {
-            let config: GrowseConfigFile =
-                toml::from_str(&std::fs::read_to_string(cli.config_file.clone().unwrap())?)?;
-            Ok(merge_config_cli(cli, &config.growse))
-        } else {
-            Ok(GrowseConfig {
-                use_branch: cli.branch.is_some() || cli.current_branch.unwrap_or(false),
-                no_show: cli.no_show.unwrap_or(false),
-                verbose: cli.verbose.unwrap_or(false),
-                current_branch: cli.current_branch.unwrap_or(false),
-            })
-        }
-    }
+        dirs::config_dir()
+            .map(|config_dir| config_dir.join(CONFIG_FILE))
+            .filter(|config_path| config_path.exists())
+    };
+    let (mut config, mut settings) = match config_path {
+        Some(config_path) => (
+            GrowseConfig::from_file(&config_path)?,
+            settings(&config_path)?,
+        ),
+        None => (GrowseConfig::default(), Settings::default()),
+    };
+
+    config.current_branch = cli.current_branch.unwrap_or(false);
+    config.use_branch = cli.branch.is_some() || config.current_branch;
+    // the flags are false rather than unset when not given
+    settings.no_show |= cli.no_show.unwrap_or(false);
+    settings.verbose |= cli.verbose.unwrap_or(false);
+    Ok((config, settings))
+}
+
+// The library ignores the command line settings in the config file
+fn settings(path: &Path) -> Result<Settings, GrowseError> {
+    let contents = std::fs::read_to_string(path).map_err(|source| GrowseError::ConfigRead {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    let settings_file: SettingsFile =
+        toml::from_str(&contents).map_err(|source| GrowseError::Config {
+            path: path.to_path_buf(),
+            source,
+        })?;
+    Ok(settings_file.growse)
 }
 
-fn run(cli: &Cli) -> Result<(), Box<dyn std::error::Error>> {
-    let config = config(cli)?;
-    if config.verbose {
-        println!("config: {:?}", config);
+fn run(cli: &Cli) -> Result<(), GrowseError> {
+    let (mut config, settings) = config(cli)?;
+    config.ssh_config = SshConfig::load();
+    if settings.verbose {
+        log::set_logger(&StderrLogger)
+            .map(|()| log::set_max_level(log::LevelFilter::Debug))
+            .ok();
     }
+    log::debug!("config: {:?}", config);
 
     // TODO check if file exists locally??
-    let (path, line_number) = if let Some(path) = cli.path.as_deref() {
-        let re = Regex::new(r"(.*?)((:)(\d+))?$").unwrap();
-        let captures = re.captures(path).unwrap();
-        let path = captures.get(1).unwrap().as_str().to_string();
-        if let Some(line_number) = captures.get(4) {
-            let x = line_number.as_str().parse::<u32>().unwrap();
-            (Some(path), Some(x))
-        } else {
-            (Some(path), None)
-        }
+    let (path, line_number, end_line_number) = if let Some(path) = cli.path.as_deref() {
+        let (path, line_number, end_line_number) = parse_path(path)?;
+        (Some(path), line_number, end_line_number)
     } else {
-        (None, None)
+        (None, None, None)
     };
 
-    let repo = Repository::open_from_env()?;
-    let current_dir = std::env::current_dir()?.to_str().unwrap().to_string();
+    let repo = Repository::open_from_env().map_err(GrowseError::from_open)?;
     let repo_dir = repo
-        .path()
-        .parent()
-        .ok_or("No parent found")?
-        .to_string_lossy()
-        .to_string();
+        .workdir()
+        .ok_or_else(|| GrowseError::PathOutsideRepo(repo.path().display().to_string()))?
+        .to_path_buf();
 
-    // probably a better way to do this...
     // construct path from repo root
-    let path = if current_dir == repo_dir {
-        path
-    } else {
-        let offset_path = current_dir.strip_prefix(&format!("{}/", repo_dir)).unwrap();
-        Some(format!("{}/{}", offset_path, path.unwrap()))
+    let path = match path {
+        Some(path) => Some(repo_path(&repo_dir, &path)?),
+        None => None,
     };
 
-    let remote_name = if cli.remote.is_none() {
-        default_remote(&repo)?
-    } else {
-        // TODO Manually check remote exists in repo??
-        cli.remote.clone().unwrap()
-    };
-    let remote = repo.find_remote(&remote_name)?;
+    log::debug!("repo_dir: {:?}", repo_dir);
 
-    let branch = if config.use_branch {
-        if config.current_branch {
-            repo.head().unwrap().shorthand().unwrap().to_string()
-        } else {
-            cli.branch.clone().unwrap()
+    let (compare_base, compare_head) = match cli.compare.as_deref() {
+        Some(range) => {
+            let (base, head) = range.split_once("...").unwrap_or((range, ""));
+            let non_empty = |rev: &str| Some(rev.to_string()).filter(|rev| !rev.is_empty());
+            (non_empty(base), non_empty(head))
         }
-    } else {
-        default_branch(&repo, &remote, &config)
+        None => (None, None),
     };
 
-    let git_url = remote.url().ok_or("No url found for remote")?;
-
-    let state = GrowseState {
-        path,
-        line_number,
-        branch,
+    let mut options = LinkOptions::default();
+    options.remote = cli.remote.clone();
+    options.branch = cli.branch.clone();
+    options.commit = cli.commit.clone().or(cli.show.clone());
+    options.compare_base = compare_base;
+    options.compare_head = compare_head;
+    options.link_kind = if cli.pr.unwrap_or(false) {
+        LinkKind::PullRequest
+    } else if cli.show.is_some() {
+        LinkKind::Commit
+    } else if cli.compare.is_some() {
+        LinkKind::Compare
+    } else if cli.blame.unwrap_or(false) {
+        LinkKind::Blame
+    } else if cli.history.unwrap_or(false) {
+        LinkKind::History
+    } else if cli.raw.unwrap_or(false) {
+        LinkKind::Raw
+    } else {
+        LinkKind::Browse
     };
+    options.config = config;
 
-    if config.verbose {
-        println!("state: {:?}", state);
-        println!("repo_dir: {:?}", repo_dir);
-    }
-
-    let link_url = remote_url_to_repo_url(git_url, &state, &config)?;
+    let lines = line_number.map(|line_number| (line_number, end_line_number));
+    let link_url = link_for(&repo_dir, path.as_deref(), lines, &options)?;
 
-    if config.no_show {
+    if settings.no_show {
         println!("{}", link_url);
     } else {
-        open_link(&link_url)?;
+        open_link(link_url.as_str())?;
     }
     Ok(())
 }
 
-fn default_remote(repo: &Repository) -> Result<String, Box<dyn std::error::Error>> {
-    let remote_names = repo.remotes()?;
-    let mut remotes = (&remote_names).into_iter().flatten();
-    if let Some(remote) = remotes.next() {
-        return Ok(remote.to_string());
-    }
-    Err("No remotes found".into())
-}
-
-fn default_branch(repo: &Repository, remote: &Remote, config: &GrowseConfig) -> String {
-    let default_branch = remote.default_branch();
-    if let Ok(default_branch) = default_branch {
-        return default_branch.as_str().unwrap().to_string();
+// Path argument relative to the current directory, as a path from the repo root
+fn repo_path(repo_dir: &Path, path: &str) -> Result<String, GrowseError> {
+    let outside = || GrowseError::PathOutsideRepo(path.to_string());
+    // resolve symlinks on both sides, e.g. a repo under a symlinked /tmp
+    let repo_dir = std::fs::canonicalize(repo_dir)?;
+    let offset_path = if Path::new(path).is_absolute() {
+        // editors pass absolute paths, e.g. vim's %:p
+        std::fs::canonicalize(path)
+            .unwrap_or_else(|_| PathBuf::from(path))
+            .strip_prefix(&repo_dir)
+            .map_err(|_| outside())?
+            .to_path_buf()
     } else {
-        let remote_ref = format!("refs/remotes/{}/HEAD", remote.name().unwrap());
-        let reference = repo.resolve_reference_from_short_name(&remote_ref);
-        if let Ok(reference) = reference {
-            let remote_ref_prefix = format!("refs/remotes/{}/", remote.name().unwrap());
-            let name = reference.name();
-            let short_name = Path::new(name.unwrap())
-                .strip_prefix(remote_ref_prefix)
-                .unwrap();
-            if config.verbose {
-                println!("reference name: {:?}", name);
-                println!("resolved: {:?}", short_name);
+        std::fs::canonicalize(std::env::current_dir()?)?
+            .strip_prefix(&repo_dir)
+            .map_err(|_| outside())?
+            .join(path)
+    };
+    // fold away . and .., a path climbing above the root is outside the work tree
+    let mut parts = vec![];
+    for component in offset_path.components() {
+        match component {
+            Component::Normal(part) => {
+                parts.push(part.to_str().ok_or(GrowseError::InvalidUtf8("Path"))?)
             }
-            if short_name != Path::new("HEAD") {
-                return short_name.to_str().unwrap().to_string();
+            Component::CurDir => {}
+            Component::ParentDir => {
+                parts.pop().ok_or_else(outside)?;
             }
-        } else if config.verbose {
-            println!("Could not resolve reference: {:?}", remote_ref);
+            Component::RootDir | Component::Prefix(_) => return Err(outside()),
         }
     }
-    // FIXME better strategy for finding a remote branch.
-    // look for local branches matching main or master??
-    "master".to_string()
+    Ok(parts.join("/"))
 }
 
-fn open_link(url: &str) -> Result<(), Box<dyn std::error::Error>> {
-    open::that(url)?;
-    Ok(())
+fn open_link(url: &str) -> Result<(), GrowseError> {
+    open::that(url).map_err(|source| GrowseError::OpenLink {
+        url: url.to_string(),
+        source,
+    })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::collections::HashMap;
-
-    static TEST_CONFIG: GrowseConfig = GrowseConfig {
-        verbose: true,
-        no_show: false,
-        use_branch: false,
-        current_branch: false,
-    };
-
-    fn generate_test_state() -> GrowseState {
-        GrowseState {
-            branch: "master".to_string(),
-            line_number: None,
-            path: None,
-        }
-    }
-
-    #[test]
-    fn test_simple_repo_link() {
-        let remote_urls = &[
-            "ssh://git@github.com/takac/git-open",
-            "https://github.com/takac/git-open",
-            "git@github.com:takac/git-open",
-        ];
-        let test_state = generate_test_state();
-        for url in remote_urls {
-            let expected = "https://github.com/takac/git-open";
-            assert_eq!(
-                expected,
-                remote_url_to_repo_url(url, &test_state, &TEST_CONFIG).unwrap()
-            );
-            assert_eq!(
-                expected,
-                remote_url_to_repo_url(&format!("{}.git", url), &test_state, &TEST_CONFIG).unwrap()
-            );
-        }
-    }
-
-    fn default_test(expected_to_input: HashMap<&str, &str>) {
-        for (expected, input) in expected_to_input {
-            assert_eq!(
-                expected,
-                remote_url_to_repo_url(input, &generate_test_state(), &TEST_CONFIG).unwrap()
-            );
-        }
-    }
-
-    #[test]
-    fn test_bb_repo_link() {
-        default_test(HashMap::from([
-            (
-                "https://bitbucket.company.com/projects/takac/repos/git-open",
-                "ssh://git@bitbucket.company.com:7999/takac/git-open.git",
-            ),
-            (
-                "https://bitbucket.gi.de/projects/dig/repos/frontend",
-                "https://bitbucket.gi.de/scm/dig/frontend.git",
-            ),
-        ]));
-    }
-
-    #[test]
-    fn test_gitlab_repo_link() {
-        default_test(HashMap::from([(
-            "https://gitlab.com/takac/side-project/git-open",
-            "git@gitlab.com:takac/side-project/git-open.git",
-        )]));
-    }
-
-    #[test]
-    fn test_simple_repo_link_with_branch() {
-        let remote_urls = &[
-            "ssh://git@github.com/takac/git-open",
-            "https://github.com/takac/git-open",
-            "git@github.com:takac/git-open",
-        ];
-        let state = GrowseState {
-            branch: "master".to_string(),
-            ..generate_test_state()
-        };
-
-        let config = GrowseConfig {
-            use_branch: true,
-            ..TEST_CONFIG
-        };
-
-        for url in remote_urls {
-            let expected = "https://github.com/takac/git-open/tree/master";
-            assert_eq!(
-                expected,
-                remote_url_to_repo_url(url, &state, &config).unwrap()
-            );
-            assert_eq!(
-                expected,
-                remote_url_to_repo_url(&format!("{}.git", url), &state, &config).unwrap()
-            );
-        }
-    }
-
-    #[test]
-    fn test_bb_repo_link_with_branch() {
-        let state = GrowseState {
-            branch: "master".to_string(),
-            ..generate_test_state()
-        };
-        let config = GrowseConfig {
-            use_branch: true,
-            ..TEST_CONFIG
-        };
-
-        let expected = "https://bitbucket.company.com/projects/takac/repos/git-open/browse?at=refs%2Fheads%2Fmaster";
-        let url = "ssh://git@bitbucket.company.com:7999/takac/git-open.git";
-        assert_eq!(
-            expected,
-            remote_url_to_repo_url(url, &state, &config).unwrap()
-        );
-    }
 
-    #[test]
-    fn test_simple_repo_link_with_path() {
-        let remote_urls = &[
-            "ssh://git@github.com/takac/git-open",
-            "https://github.com/takac/git-open",
-            "git@github.com:takac/git-open",
-        ];
-        let state = GrowseState {
-            branch: "main".to_string(),
-            path: Some("src/main.rs".to_string()),
-            ..generate_test_state()
-        };
-
-        for url in remote_urls {
-            let expected = "https://github.com/takac/git-open/blob/main/src/main.rs";
-            assert_eq!(
-                expected,
-                remote_url_to_repo_url(url, &state, &TEST_CONFIG).unwrap()
-            );
-            assert_eq!(
-                expected,
-                remote_url_to_repo_url(&format!("{}.git", url), &state, &TEST_CONFIG).unwrap()
-            );
-        }
+    // temp_dir/repo/src/lib.rs and temp_dir/other.rs
+    fn create_test_dirs(name: &str) -> (PathBuf, PathBuf) {
+        let temp_dir =
+            std::env::temp_dir().join(format!("growse-test-{name}-{}", std::process::id()));
+        let repo_dir = temp_dir.join("repo");
+        std::fs::create_dir_all(repo_dir.join("src")).unwrap();
+        std::fs::write(repo_dir.join("src/lib.rs"), "").unwrap();
+        std::fs::write(temp_dir.join("other.rs"), "").unwrap();
+        (temp_dir, repo_dir)
     }
 
     #[test]
-    fn test_simple_repo_link_with_path_not_at_root() {
-        let remote_urls = &[
-            "ssh://git@github.com/takac/git-open",
-            "https://github.com/takac/git-open",
-            "git@github.com:takac/git-open",
-        ];
-        let state = GrowseState {
-            branch: "main".to_string(),
-            path: Some("src/main.rs".to_string()),
-            ..generate_test_state()
-        };
-
-        for url in remote_urls {
-            let expected = "https://github.com/takac/git-open/blob/main/src/main.rs";
-            assert_eq!(
-                expected,
-                remote_url_to_repo_url(url, &state, &TEST_CONFIG).unwrap()
-            );
-            assert_eq!(
-                expected,
-                remote_url_to_repo_url(&format!("{}.git", url), &state, &TEST_CONFIG).unwrap()
-            );
-        }
-    }
+    fn test_repo_path_absolute() {
+        let (temp_dir, repo_dir) = create_test_dirs("absolute-path");
+        let path = repo_path(&repo_dir, repo_dir.join("src/lib.rs").to_str().unwrap());
+        std::fs::remove_dir_all(&temp_dir).unwrap();
 
-    #[test]
-    fn test_simple_repo_link_with_path_line_nos() {
-        let remote_urls = &[
-            "ssh://git@github.com/takac/git-open",
-            "https://github.com/takac/git-open",
-            "git@github.com:takac/git-open",
-        ];
-        let state = GrowseState {
-            branch: "main".to_string(),
-            path: Some("src/main.rs".to_string()),
-            line_number: Some(10),
-        };
-
-        for url in remote_urls {
-            let expected = "https://github.com/takac/git-open/blob/main/src/main.rs#L10";
-            assert_eq!(
-                expected,
-                remote_url_to_repo_url(url, &state, &TEST_CONFIG).unwrap()
-            );
-        }
-    }
-
-    #[test]
-    fn test_repo_link_with_remote() {
-        let remote_urls = &[
-            "ssh://git@github.com/takac/git-open",
-            "https://github.com/takac/git-open",
-            "git@github.com:takac/git-open",
-        ];
-        let state = GrowseState {
-            branch: "main".to_string(),
-            path: Some("src/main.rs".to_string()),
-            line_number: Some(10),
-        };
-
-        for url in remote_urls {
-            let expected = "https://github.com/takac/git-open/blob/main/src/main.rs#L10";
-            assert_eq!(
-                expected,
-                remote_url_to_repo_url(url, &state, &TEST_CONFIG).unwrap()
-            );
-        }
+        assert_eq!("src/lib.rs", path.unwrap());
     }
 
     #[test]
-    fn test_load_config() {
-        let config: GrowseConfigFile = toml::from_str(
-            r#"
-            [growse]
-            verbose = true
-            "#,
-        )
-        .unwrap();
-        assert!(config.growse.verbose);
+    fn test_repo_path_absolute_outside() {
+        let (temp_dir, repo_dir) = create_test_dirs("absolute-outside");
+        let path = repo_path(&repo_dir, temp_dir.join("other.rs").to_str().unwrap());
+        std::fs::remove_dir_all(&temp_dir).unwrap();
+
+        let err = path.unwrap_err();
+        assert!(matches!(err, GrowseError::PathOutsideRepo(_)));
+        assert_eq!(4, err.exit_code());
     }
 }
@@ -0,0 +1,325 @@
+use crate::*;
+
+/// How sure a provider is that it serves a remote, the most confident
+/// provider builds the links.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Confidence {
+    /// Not a remote of this service.
+    No,
+    /// Only used when nothing else matches, e.g. the generic backend.
+    Fallback,
+    /// The host name contains the service name, e.g. `mygithubmirror.corp.example`.
+    Mentioned,
+    /// A label of the host name is the service name, e.g. `gitlab.corp.example`.
+    Named,
+    /// A well known host or the default ssh port of the service.
+    Known,
+    /// Set up for the host in `growse.toml`.
+    Configured,
+}
+
+impl Confidence {
+    /// How strongly `host` names the service `name`.
+    pub fn mentioned(host: &str, name: &str) -> Confidence {
+        if host.split('.').any(|label| label == name) {
+            Confidence::Named
+        } else if host.contains(name) {
+            Confidence::Mentioned
+        } else {
+            Confidence::No
+        }
+    }
+}
+
+/// A hosting service growse can build links for.
+pub trait Provider {
+    /// Values of `backend` in the `[hosts]` entries of `growse.toml` that
+    /// select this provider, e.g. `github`.
+    fn backends(&self) -> &[&str];
+    /// How sure the provider is that it serves the remote.
+    fn confidence(&self, url: &GitUrl, config: &GrowseConfig) -> Confidence;
+    /// Breaks ties between providers with the same confidence, the highest wins
+    /// and after that the first registered.
+    fn priority(&self) -> i32 {
+        0
+    }
+    /// Links for the remote.
+    fn repo<'a>(
+        &self,
+        url: &'a GitUrl,
+        config: &'a GrowseConfig,
+        state: &'a GrowseState,
+    ) -> Box<dyn Repo + 'a>;
+}
+
+/// The providers a remote is matched against, [`Registry::default`] holds
+/// every built-in backend.
+pub struct Registry {
+    providers: Vec<Box<dyn Provider>>,
+}
+
+impl Default for Registry {
+    fn default() -> Self {
+        let mut registry = Registry::new();
+        registry.register(Box::new(template::TemplateProvider));
+        registry.register(Box::new(github::GitHubProvider));
+        registry.register(Box::new(gitlab::GitLabProvider));
+        registry.register(Box::new(bitbucket_cloud::BitBucketCloudProvider));
+        registry.register(Box::new(bitbucket::BitBucketProvider));
+        registry.register(Box::new(gitea::GiteaProvider));
+        registry.register(Box::new(sourcehut::SourceHutProvider));
+        registry.register(Box::new(azure::AzureDevOpsProvider));
+        registry.register(Box::new(gerrit::GerritProvider));
+        registry.register(Box::new(codecommit::CodeCommitProvider));
+        registry.register(Box::new(cgit::CgitProvider));
+        registry.register(Box::new(gitweb::GitwebProvider));
+        registry.register(Box::new(generic::GenericProvider));
+        registry
+    }
+}
+
+impl Registry {
+    /// An empty registry, see [`Registry::default`] for the built-in backends.
+    pub fn new() -> Self {
+        Registry { providers: vec![] }
+    }
+
+    /// Add a provider, it's matched against remotes alongside the others.
+    pub fn register(&mut self, provider: Box<dyn Provider>) {
+        self.providers.push(provider);
+    }
+
+    /// The provider configured for the remote's host, otherwise the most
+    /// confident one.
+    pub fn provider(
+        &self,
+        url: &GitUrl,
+        config: &GrowseConfig,
+    ) -> Result<Option<&dyn Provider>, GrowseError> {
+        if let Some(host) = url.host.as_deref() {
+            if let Some(backend) = config.backend_for(Some(host)) {
+                return self
+                    .providers
+                    .iter()
+                    .find(|provider| provider.backends().contains(&backend))
+                    .map(|provider| Some(provider.as_ref()))
+                    .ok_or_else(|| GrowseError::UnknownBackend {
+                        host: host.to_string(),
+                        backend: backend.to_string(),
+                    });
+            }
+        }
+
+        let mut best: Option<(Confidence, i32, &dyn Provider)> = None;
+        for provider in &self.providers {
+            let confidence = provider.confidence(url, config);
+            if confidence == Confidence::No {
+                continue;
+            }
+            let score = (confidence, provider.priority());
+            if best.map_or(true, |(confidence, priority, _)| {
+                score > (confidence, priority)
+            }) {
+                best = Some((score.0, score.1, provider.as_ref()));
+            }
+        }
+        Ok(best.map(|(_, _, provider)| provider))
+    }
+
+    fn parse_remote_url(
+        &self,
+        remote_url: &str,
+        config: &GrowseConfig,
+    ) -> Result<GitUrl, GrowseError> {
+        let mut url = GitUrl::parse(&codecommit::normalize_grc_url(remote_url)?).map_err(|e| {
+            GrowseError::InvalidRemote {
+                remote: remote_url.to_string(),
+                reason: e.to_string(),
+            }
+        })?;
+        // ssh remotes can use a Host alias from ~/.ssh/config, e.g. to pick a key
+        if url.scheme == Scheme::Ssh {
+            if let Some(hostname) = url
+                .host
+                .as_deref()
+                .and_then(|host| config.ssh_config.hostname(host))
+            {
+                let resolved = GitUrl {
+                    host: Some(hostname),
+                    ..url.clone()
+                };
+                if !self.keeps_host(&url, &resolved, config) {
+                    url = resolved;
+                }
+            }
+        }
+
+        Ok(url)
+    }
+
+    // The HostName of a host growse already knows is often an ssh only
+    // endpoint, e.g. ssh.github.com for ssh over port 443, so the remote's own
+    // host is kept when it's configured or detected at least as confidently.
+    fn keeps_host(&self, url: &GitUrl, resolved: &GitUrl, config: &GrowseConfig) -> bool {
+        let configured = url
+            .host
+            .as_deref()
+            .is_some_and(|host| config.hosts.contains_key(host));
+        let confidence = self.confidence(url, config);
+        configured
+            || (confidence > Confidence::Fallback
+                && confidence >= self.confidence(resolved, config))
+    }
+
+    fn confidence(&self, url: &GitUrl, config: &GrowseConfig) -> Confidence {
+        self.providers
+            .iter()
+            .map(|provider| provider.confidence(url, config))
+            .max()
+            .unwrap_or(Confidence::No)
+    }
+
+    /// Link for a remote url, see [`remote_url_to_repo_url`].
+    pub fn link(
+        &self,
+        remote_url: &str,
+        state: &GrowseState,
+        config: &GrowseConfig,
+    ) -> Result<String, GrowseError> {
+        let url = self.parse_remote_url(remote_url, config)?;
+        let provider = self
+            .provider(&url, config)?
+            .ok_or_else(|| GrowseError::UnknownHost {
+                remote: remote_url.to_string(),
+                host: url.host.clone(),
+            })?;
+        let repo = provider.repo(&url, config, state);
+
+        match state.link_kind {
+            LinkKind::PullRequest => return repo.to_pull_request_url(),
+            LinkKind::Commit => return repo.to_commit_url(),
+            LinkKind::Compare => return repo.to_compare_url(),
+            LinkKind::Blame => return repo.to_blame_url(),
+            LinkKind::History => return repo.to_history_url(),
+            LinkKind::Raw => return repo.to_raw_url(),
+            LinkKind::Browse => {}
+        }
+        if config.use_branch || state.commit.is_some() {
+            if state.path.is_some() {
+                if state.line_number.is_some() {
+                    repo.to_repo_url_with_path_and_branch_and_line_number()
+                } else {
+                    repo.to_repo_url_with_path_and_branch()
+                }
+            } else {
+                repo.to_repo_url_with_branch()
+            }
+        } else if state.path.is_some() {
+            if state.line_number.is_some() {
+                repo.to_repo_url_with_path_and_line_number()
+            } else {
+                repo.to_repo_url_with_path()
+            }
+        } else {
+            repo.to_repo_url()
+        }
+    }
+
+    /// Link to the web interface for the repository at `repo_path`, optionally to
+    /// `file`, a path from the root of the work tree, and its `lines` as the first
+    /// and optional last line, see [`link_for`].
+    pub fn link_for(
+        &self,
+        repo_path: &Path,
+        file: Option<&str>,
+        lines: Option<(u32, Option<u32>)>,
+        options: &LinkOptions,
+    ) -> Result<Url, GrowseError> {
+        let mut config = options.config.clone();
+        config.use_branch = config.use_branch || config.current_branch || options.branch.is_some();
+
+        let repo = Repository::discover(repo_path).map_err(GrowseError::from_open)?;
+        let remote_name = match options.remote.clone() {
+            Some(remote_name) => remote_name,
+            None => default_remote(&repo)?,
+        };
+        let remote = repo.find_remote(&remote_name).map_err(|e| match e.code() {
+            ErrorCode::NotFound | ErrorCode::InvalidSpec => {
+                GrowseError::NoRemote(Some(remote_name))
+            }
+            _ => GrowseError::Git(e),
+        })?;
+
+        let pull_request = options.link_kind == LinkKind::PullRequest;
+        let change_id = if pull_request {
+            head_change_id(&repo)
+        } else {
+            None
+        };
+        let branch = if let Some(branch) = options.branch.clone() {
+            branch
+        } else if pull_request {
+            // Gerrit changes are found by Change-Id, so a detached HEAD is fine
+            match current_branch(&repo) {
+                Ok(branch) => branch,
+                Err(_) if change_id.is_some() => default_branch(&repo, &remote)?,
+                Err(e) => return Err(e),
+            }
+        } else if config.current_branch {
+            current_branch(&repo)?
+        } else {
+            default_branch(&repo, &remote)?
+        };
+
+        let commit = if let Some(rev) = options.commit.as_deref() {
+            Some(resolve_commit(&repo, rev)?)
+        } else {
+            None
+        };
+
+        let compare = if options.link_kind == LinkKind::Compare {
+            let base = match options.compare_base.as_deref() {
+                Some(base) => resolve_compare_ref(&repo, base)?,
+                None => format!("refs/heads/{}", default_branch(&repo, &remote)?),
+            };
+            let head = match options.compare_head.as_deref() {
+                Some(head) => resolve_compare_ref(&repo, head)?,
+                None => format!("refs/heads/{}", current_branch(&repo)?),
+            };
+            Some((base, head))
+        } else {
+            None
+        };
+
+        // libgit2 has already applied the url.<base>.insteadOf rules
+        let git_url = remote.url().ok_or(GrowseError::InvalidUtf8("Remote url"))?;
+
+        let state = GrowseState {
+            path: file.map(str::to_string),
+            line_number: lines.map(|(line_number, _)| line_number),
+            end_line_number: lines.and_then(|(_, end_line_number)| end_line_number),
+            branch,
+            commit,
+            compare,
+            change_id,
+            link_kind: options.link_kind.clone(),
+        };
+
+        log::debug!("state: {:?}", state);
+
+        let link_url = match self.link(git_url, &state, &config) {
+            // a remote only rewritten for pushing, libgit2 applies the
+            // url.<base>.pushInsteadOf rules to the push url
+            Err(e @ (GrowseError::UnknownHost { .. } | GrowseError::InvalidRemote { .. })) => {
+                match remote.pushurl() {
+                    Some(push_url) if push_url != git_url => {
+                        self.link(push_url, &state, &config).map_err(|_| e)?
+                    }
+                    _ => return Err(e),
+                }
+            }
+            link_url => link_url?,
+        };
+        Ok(Url::parse(&link_url)?)
+    }
+}
@@ -0,0 +1,133 @@
+use crate::*;
+
+pub struct SourceHut<'a> {
+    url: &'a GitUrl,
+    config: &'a GrowseConfig,
+    state: &'a GrowseState,
+}
+
+pub struct SourceHutProvider;
+
+impl Provider for SourceHutProvider {
+    fn backends(&self) -> &[&str] {
+        &["sourcehut"]
+    }
+
+    fn confidence(&self, url: &GitUrl, _config: &GrowseConfig) -> Confidence {
+        match url.host.as_deref() {
+            Some(host) if host.contains("sr.ht") => Confidence::Known,
+            _ => Confidence::No,
+        }
+    }
+
+    fn repo<'a>(
+        &self,
+        url: &'a GitUrl,
+        config: &'a GrowseConfig,
+        state: &'a GrowseState,
+    ) -> Box<dyn Repo + 'a> {
+        Box::new(SourceHut { url, config, state })
+    }
+}
+
+// No pull request or compare pages, patches are sent with git send-email
+impl Repo for SourceHut<'_> {
+    fn name(&self) -> String {
+        "SourceHut".to_string()
+    }
+
+    fn to_repo_url_with_path_and_branch(&self) -> Result<String, GrowseError> {
+        self.to_repo_url_with_path()
+    }
+
+    fn to_repo_url_with_path(&self) -> Result<String, GrowseError> {
+        let url = self.to_repo_url_with_branch()?;
+        let path = encode(self.state.path()?);
+
+        Ok(format!("{url}/item/{path}"))
+    }
+
+    fn to_repo_url_with_path_and_branch_and_line_number(&self) -> Result<String, GrowseError> {
+        self.to_repo_url_with_path_and_line_number()
+    }
+
+    fn to_repo_url_with_path_and_line_number(&self) -> Result<String, GrowseError> {
+        let url = self.to_repo_url_with_path()?;
+        let fragment = self.state.line_fragment("")?;
+
+        Ok(format!("{url}#{fragment}"))
+    }
+
+    fn to_repo_url_with_branch(&self) -> Result<String, GrowseError> {
+        let repo_url = self.to_repo_url()?;
+        let git_ref = encode(self.state.git_ref());
+
+        Ok(format!("{repo_url}/tree/{git_ref}"))
+    }
+
+    fn to_commit_url(&self) -> Result<String, GrowseError> {
+        let repo_url = self.to_repo_url()?;
+        let commit = self.state.commit()?;
+
+        Ok(format!("{repo_url}/commit/{commit}"))
+    }
+
+    fn to_blame_url(&self) -> Result<String, GrowseError> {
+        let repo_url = self.to_repo_url()?;
+        let git_ref = encode(self.state.git_ref());
+        let path = encode(self.state.path()?);
+        let url = format!("{repo_url}/blame/{git_ref}/{path}");
+
+        if self.state.line_number.is_some() {
+            let fragment = self.state.line_fragment("")?;
+            Ok(format!("{url}#{fragment}"))
+        } else {
+            Ok(url)
+        }
+    }
+
+    fn to_history_url(&self) -> Result<String, GrowseError> {
+        let repo_url = self.to_repo_url()?;
+        let git_ref = encode(self.state.git_ref());
+        let url = format!("{repo_url}/log/{git_ref}");
+
+        match &self.state.path {
+            Some(path) => Ok(format!("{url}/item/{}", encode(path))),
+            None => Ok(url),
+        }
+    }
+
+    fn to_raw_url(&self) -> Result<String, GrowseError> {
+        let repo_url = self.to_repo_url()?;
+        let git_ref = encode(self.state.git_ref());
+        let path = encode(self.state.path()?);
+
+        Ok(format!("{repo_url}/blob/{git_ref}/{path}"))
+    }
+
+    fn to_repo_url(&self) -> Result<String, GrowseError> {
+        let base_url = self.config.base_url(self.url)?;
+        let owner = self.owner()?;
+        let name = &self.url.name;
+
+        Ok(format!("{base_url}/{owner}/{name}"))
+    }
+}
+
+impl SourceHut<'_> {
+    // Owners are always prefixed with ~, which some remotes percent-encode or drop
+    fn owner(&self) -> Result<String, GrowseError> {
+        let owner = self
+            .url
+            .owner
+            .clone()
+            .ok_or(GrowseError::MissingRemotePart("owner"))?;
+        let owner = owner
+            .strip_prefix("%7E")
+            .or_else(|| owner.strip_prefix("%7e"))
+            .or_else(|| owner.strip_prefix('~'))
+            .unwrap_or(&owner);
+
+        Ok(format!("~{owner}"))
+    }
+}
@@ -0,0 +1,168 @@
+use std::path::{Path, PathBuf};
+
+// OpenSSH gives up on deeper Include chains too
+const MAX_INCLUDE_DEPTH: usize = 16;
+
+/// HostName settings from ~/.ssh/config, used to map ssh aliases such as
+/// `github-work` back to the host they connect to.
+#[derive(Debug, Clone, Default)]
+pub struct SshConfig {
+    // Host patterns and the HostName set under them, in file order
+    hostnames: Vec<(Vec<String>, String)>,
+}
+
+impl SshConfig {
+    /// Read `~/.ssh/config`, empty when it's missing.
+    pub fn load() -> SshConfig {
+        let Some(ssh_dir) = dirs::home_dir().map(|home| home.join(".ssh")) else {
+            return SshConfig::default();
+        };
+        let contents = std::fs::read_to_string(ssh_dir.join("config")).unwrap_or_default();
+        SshConfig::parse(&contents, &ssh_dir)
+    }
+
+    /// Parse an ssh config, relative `Include`s are looked up in `ssh_dir`.
+    pub fn parse(contents: &str, ssh_dir: &Path) -> SshConfig {
+        let mut config = SshConfig::default();
+        config.read(contents, ssh_dir, &[], 0);
+        config
+    }
+
+    /// HostName that `alias` connects to, like ssh the first that applies wins.
+    pub fn hostname(&self, alias: &str) -> Option<String> {
+        self.hostnames
+            .iter()
+            .find(|(patterns, _)| host_matches(patterns, alias))
+            .map(|(_, hostname)| hostname.replace("%h", alias).replace("%%", "%"))
+    }
+
+    fn read_file(&mut self, path: &Path, ssh_dir: &Path, patterns: &[String], depth: usize) {
+        // a missing or unreadable file is skipped, same as ssh
+        if let Ok(contents) = std::fs::read_to_string(path) {
+            self.read(&contents, ssh_dir, patterns, depth);
+        }
+    }
+
+    fn read(&mut self, contents: &str, ssh_dir: &Path, patterns: &[String], depth: usize) {
+        let mut patterns = patterns.to_vec();
+        for line in contents.lines() {
+            let Some((keyword, value)) = split_line(line) else {
+                continue;
+            };
+            match keyword.to_lowercase().as_str() {
+                "host" => patterns = value.split_whitespace().map(unquote).collect(),
+                // Match criteria aren't supported, never apply its settings
+                "match" => patterns = vec!["!*".to_string()],
+                "hostname" => self.hostnames.push((patterns.clone(), unquote(value))),
+                "include" if depth < MAX_INCLUDE_DEPTH => {
+                    for file in value.split_whitespace() {
+                        for path in include_paths(&unquote(file), ssh_dir) {
+                            self.read_file(&path, ssh_dir, &patterns, depth + 1);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+// `Keyword value` or `Keyword=value`, skipping blanks and comments
+fn split_line(line: &str) -> Option<(&str, &str)> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+    let end = line.find(|c: char| c.is_whitespace() || c == '=')?;
+    let (keyword, value) = line.split_at(end);
+    let value = value.trim_start();
+    let value = value.strip_prefix('=').unwrap_or(value).trim();
+    Some((keyword, value))
+}
+
+fn unquote(value: &str) -> String {
+    value.trim_matches('"').to_string()
+}
+
+// Settings before the first Host line apply to every host. Otherwise one
+// pattern has to match and no negated pattern may.
+fn host_matches(patterns: &[String], host: &str) -> bool {
+    if patterns.is_empty() {
+        return true;
+    }
+    let host = host.to_lowercase();
+    let mut matched = false;
+    for pattern in patterns.iter().map(|pattern| pattern.to_lowercase()) {
+        if let Some(pattern) = pattern.strip_prefix('!') {
+            if wildcard_match(pattern, &host) {
+                return false;
+            }
+        } else if wildcard_match(&pattern, &host) {
+            matched = true;
+        }
+    }
+    matched
+}
+
+// `*` and `?` wildcards as used by ssh host patterns and Include globs
+fn wildcard_match(pattern: &str, value: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let value: Vec<char> = value.chars().collect();
+    let (mut p, mut v) = (0, 0);
+    let mut backtrack = None;
+    while v < value.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == value[v]) {
+            p += 1;
+            v += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, v));
+            p += 1;
+        } else if let Some((star, matched)) = backtrack {
+            p = star + 1;
+            v = matched + 1;
+            backtrack = Some((star, matched + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+// Relative Include paths are under ~/.ssh, wildcards are only expanded in the
+// file name which covers the usual `Include config.d/*`.
+fn include_paths(file: &str, ssh_dir: &Path) -> Vec<PathBuf> {
+    let path = match file.strip_prefix("~/") {
+        Some(rest) => match dirs::home_dir() {
+            Some(home) => home.join(rest),
+            None => return vec![],
+        },
+        None => ssh_dir.join(file),
+    };
+    let Some(name) = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+    else {
+        return vec![];
+    };
+    if !name.contains(['*', '?']) {
+        return vec![path];
+    }
+    let Some(dir) = path.parent() else {
+        return vec![];
+    };
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return vec![];
+    };
+    let mut paths: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.is_file()
+                && path
+                    .file_name()
+                    .is_some_and(|file_name| wildcard_match(&name, &file_name.to_string_lossy()))
+        })
+        .collect();
+    paths.sort();
+    paths
+}
@@ -0,0 +1,215 @@
+use crate::*;
+use std::sync::OnceLock;
+
+pub struct Template<'a> {
+    url: &'a GitUrl,
+    config: &'a GrowseConfig,
+    state: &'a GrowseState,
+}
+
+pub struct TemplateProvider;
+
+impl Provider for TemplateProvider {
+    fn backends(&self) -> &[&str] {
+        &[]
+    }
+
+    fn confidence(&self, url: &GitUrl, config: &GrowseConfig) -> Confidence {
+        let templates = url
+            .host
+            .as_deref()
+            .and_then(|host| config.hosts.get(host))
+            .and_then(|host_config| host_config.templates.as_ref());
+        if templates.is_some() {
+            Confidence::Configured
+        } else {
+            Confidence::No
+        }
+    }
+
+    fn repo<'a>(
+        &self,
+        url: &'a GitUrl,
+        config: &'a GrowseConfig,
+        state: &'a GrowseState,
+    ) -> Box<dyn Repo + 'a> {
+        Box::new(Template { url, config, state })
+    }
+}
+
+/// URL templates for a forge without a built-in backend, configured per host:
+///
+/// ```toml
+/// [hosts."git.corp.example".templates]
+/// file = "https://{host}/{owner}/{repo}/src/{ref}/{path}"
+/// ```
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct UrlTemplates {
+    repo: Option<String>,
+    branch: Option<String>,
+    file: Option<String>,
+    line: Option<String>,
+    range: Option<String>,
+    commit: Option<String>,
+    compare: Option<String>,
+    pull_request: Option<String>,
+    blame: Option<String>,
+    history: Option<String>,
+    raw: Option<String>,
+}
+
+impl Repo for Template<'_> {
+    fn name(&self) -> String {
+        format!(
+            "The url templates of {}",
+            self.url.host.as_deref().unwrap_or_default()
+        )
+    }
+
+    fn to_repo_url_with_path_and_branch(&self) -> Result<String, GrowseError> {
+        self.to_repo_url_with_path()
+    }
+
+    fn to_repo_url_with_path(&self) -> Result<String, GrowseError> {
+        self.render("file", |templates| &templates.file)
+    }
+
+    fn to_repo_url_with_path_and_branch_and_line_number(&self) -> Result<String, GrowseError> {
+        self.to_repo_url_with_path_and_line_number()
+    }
+
+    fn to_repo_url_with_path_and_line_number(&self) -> Result<String, GrowseError> {
+        if self.state.end_line_number.is_some() {
+            self.render("range", |templates| &templates.range)
+        } else {
+            self.render("line", |templates| &templates.line)
+        }
+    }
+
+    fn to_repo_url_with_branch(&self) -> Result<String, GrowseError> {
+        self.render("branch", |templates| &templates.branch)
+    }
+
+    fn to_pull_request_url(&self) -> Result<String, GrowseError> {
+        self.render("pull_request", |templates| &templates.pull_request)
+    }
+
+    fn to_commit_url(&self) -> Result<String, GrowseError> {
+        self.render("commit", |templates| &templates.commit)
+    }
+
+    fn to_compare_url(&self) -> Result<String, GrowseError> {
+        self.render("compare", |templates| &templates.compare)
+    }
+
+    fn to_blame_url(&self) -> Result<String, GrowseError> {
+        self.render("blame", |templates| &templates.blame)
+    }
+
+    fn to_history_url(&self) -> Result<String, GrowseError> {
+        self.render("history", |templates| &templates.history)
+    }
+
+    fn to_raw_url(&self) -> Result<String, GrowseError> {
+        self.render("raw", |templates| &templates.raw)
+    }
+
+    fn to_repo_url(&self) -> Result<String, GrowseError> {
+        self.render("repo", |templates| &templates.repo)
+    }
+}
+
+impl Template<'_> {
+    fn templates(&self) -> Option<&UrlTemplates> {
+        let host = self.url.host.as_deref()?;
+        self.config.hosts.get(host)?.templates.as_ref()
+    }
+
+    fn render(
+        &self,
+        kind: &str,
+        template: impl Fn(&UrlTemplates) -> &Option<String>,
+    ) -> Result<String, GrowseError> {
+        let host = self
+            .url
+            .host
+            .clone()
+            .ok_or(GrowseError::MissingRemotePart("host"))?;
+        let templates = self
+            .templates()
+            .ok_or_else(|| GrowseError::InvalidTemplate(format!("none configured for {host}")))?;
+        let template = template(templates)
+            .as_deref()
+            .ok_or_else(|| GrowseError::unsupported(&self.name(), kind))?;
+
+        static PLACEHOLDER: OnceLock<Regex> = OnceLock::new();
+        let re = PLACEHOLDER.get_or_init(|| {
+            Regex::new(r"\{([a-z_]+)\}").expect("the placeholder pattern is valid")
+        });
+        let mut rendered = String::new();
+        let mut last = 0;
+        for captures in re.captures_iter(template) {
+            let placeholder = captures.get(0).expect("group 0 is the whole match");
+            let name = &captures[1];
+            let value = self.placeholder(name)?.ok_or_else(|| {
+                GrowseError::InvalidTemplate(format!(
+                    "no value for {{{name}}} in the {kind} template for {host}"
+                ))
+            })?;
+            rendered.push_str(&template[last..placeholder.start()]);
+            // base_url is a URL prefix, everything else is a value inside one
+            if name == "base_url" {
+                rendered.push_str(&value);
+            } else {
+                rendered.push_str(&encode(&value));
+            }
+            last = placeholder.end();
+        }
+        rendered.push_str(&template[last..]);
+
+        Ok(rendered)
+    }
+
+    fn placeholder(&self, name: &str) -> Result<Option<String>, GrowseError> {
+        let url = &self.url;
+        let state = &self.state;
+        let remote_path = url.path.trim_matches('/');
+        let compare = state.compare.as_ref();
+
+        let value = match name {
+            // remote
+            "base_url" => Some(self.config.base_url(url)?),
+            "host" => url.host.clone(),
+            "port" => url.port.map(|port| port.to_string()),
+            "scheme" => Some(url.scheme.to_string()),
+            "user" => url.user.clone(),
+            "owner" => url.owner.clone(),
+            "organization" => url.organization.clone(),
+            "name" | "repo" => Some(url.name.clone()),
+            "fullname" => Some(url.fullname.clone()),
+            "remote_path" => Some(
+                remote_path
+                    .strip_suffix(".git")
+                    .unwrap_or(remote_path)
+                    .to_string(),
+            ),
+            // state
+            "path" => state.path.clone(),
+            "line" => state.line_number.map(|line| line.to_string()),
+            "end_line" => state.end_line_number.map(|line| line.to_string()),
+            "ref" => Some(state.git_ref().to_string()),
+            "branch" => Some(state.branch.clone()),
+            "commit" => state.commit.clone(),
+            "base" => compare.map(|(base, _)| short_ref(base).to_string()),
+            "head" => compare.map(|(_, head)| short_ref(head).to_string()),
+            "change_id" => state.change_id.clone(),
+            _ => {
+                return Err(GrowseError::InvalidTemplate(format!(
+                    "unknown placeholder {{{name}}}"
+                )))
+            }
+        };
+
+        Ok(value)
+    }
+}